@@ -2,7 +2,7 @@ use rand::seq::SliceRandom;
 use rand::thread_rng;
 
 pub fn get_random_ascii_art() -> Vec<&'static str> {
-    let ascii_arts = vec![
+    let ascii_arts = [
         vec![
             "       *   ,MMM8&&&.     *      ",
             "           MMMM88&&&&&    .     ",
@@ -0,0 +1,388 @@
+//! Problems found while loading the config, with enough context to fix them.
+
+use super::{nested, Config};
+use serde_yml::Value;
+use std::error::Error;
+use std::fmt;
+
+/// One problem in one config layer.
+pub struct Diagnostic {
+    /// Where the problem is: a file path, an environment variable or a flag.
+    source: String,
+    /// The offending line, if the problem is in a file.
+    position: Option<Position>,
+    message: String,
+}
+
+/// 1-based line and column, plus the text of that line.
+struct Position {
+    line: usize,
+    column: usize,
+    text: String,
+}
+
+impl Diagnostic {
+    pub fn new(source: impl Into<String>, message: impl Into<String>) -> Self {
+        Diagnostic {
+            source: source.into(),
+            position: None,
+            message: message.into(),
+        }
+    }
+
+    /// Points the diagnostic at byte `offset` of `contents`.
+    fn at_offset(self, contents: &str, offset: usize) -> Self {
+        let offset = offset.min(contents.len());
+        let before = &contents[..offset];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        let column = before[line_start..].chars().count() + 1;
+        self.at(contents, line, column)
+    }
+
+    /// Points the diagnostic at a 1-based `line` and `column` of `contents`.
+    /// Some parsers report column 0 for the end of input after a newline;
+    /// that is shown as column 1.
+    fn at(mut self, contents: &str, line: usize, column: usize) -> Self {
+        let line = line.max(1);
+        let text = contents.lines().nth(line - 1).unwrap_or("");
+        self.position = Some(Position {
+            line,
+            column: column.max(1),
+            text: text.to_string(),
+        });
+        self
+    }
+
+    /// Describes a parse or type error in a config file, locating it where
+    /// the format's error reports a position.
+    pub fn from_parse_error(path: &str, contents: &str, err: &(dyn Error + 'static)) -> Self {
+        if let Some(err) = err.downcast_ref::<serde_yml::Error>() {
+            let message = err.to_string();
+            // The position is shown separately, so drop the one in the message.
+            let message = message
+                .split_once(" at line ")
+                .map_or(message.as_str(), |(message, _)| message);
+            let diagnostic = Diagnostic::new(path, message);
+            return match err.location() {
+                Some(location) => diagnostic.at_offset(contents, location.index()),
+                None => diagnostic,
+            };
+        }
+        if let Some(err) = err.downcast_ref::<toml::de::Error>() {
+            let diagnostic = Diagnostic::new(path, err.message().trim());
+            return match err.span() {
+                Some(span) => diagnostic.at_offset(contents, span.start),
+                None => diagnostic,
+            };
+        }
+        if let Some(err) = err.downcast_ref::<serde_json::Error>() {
+            let message = err.to_string();
+            let message = message
+                .split_once(" at line ")
+                .map_or(message.as_str(), |(message, _)| message);
+            let diagnostic = Diagnostic::new(path, message);
+            return match err.line() {
+                0 => diagnostic,
+                line => diagnostic.at(contents, line, err.column()),
+            };
+        }
+        Diagnostic::new(path, err.to_string())
+    }
+
+    /// Points the diagnostic at where `path` is set in `contents`.
+    fn at_key(self, contents: Option<&str>, path: &[String]) -> Self {
+        match contents.and_then(|contents| Some((contents, key_offset(contents, path)?))) {
+            Some((contents, offset)) => self.at_offset(contents, offset),
+            None => self,
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(position) = &self.position else {
+            return write!(f, "nekofetch: {}: {}", self.source, self.message);
+        };
+        writeln!(
+            f,
+            "nekofetch: {}:{}:{}: {}",
+            self.source, position.line, position.column, self.message
+        )?;
+        let gutter = " ".repeat(position.line.to_string().len());
+        writeln!(f, "{} |", gutter)?;
+        writeln!(f, "{} | {}", position.line, position.text)?;
+        write!(f, "{} | {}^", gutter, " ".repeat(position.column - 1))
+    }
+}
+
+/// Removes every key from `layer` that is unknown or holds a value of the
+/// wrong type, returning a diagnostic for each so the rest of the layer can
+/// still apply. `known` is the tree of defaults; only mappings are walked, so
+/// list and free-form values are never flagged as unknown.
+pub fn check_layer(
+    source: &str,
+    contents: Option<&str>,
+    layer: &mut Value,
+    known: &Value,
+) -> Vec<Diagnostic> {
+    let mut found = Vec::new();
+    let mut path = Vec::new();
+    prune_unknown(layer, known, &mut path, &mut |path, candidates| {
+        let (key, parent) = path.split_last().expect("unknown key has a name");
+        let prefix: String = parent.iter().map(|part| format!("{}.", part)).collect();
+        let mut message = format!("unknown key `{}{}`", prefix, key);
+        if let Some(suggestion) = closest(key, candidates) {
+            message.push_str(&format!(", did you mean `{}{}`?", prefix, suggestion));
+        }
+        found.push(Diagnostic::new(source, message).at_key(contents, path));
+    });
+
+    // Each pass drops one bad value, so the loop ends once the rest deserializes.
+    while let Err(err) = serde_yml::from_value::<Config>(layer.clone()) {
+        let Some((path, err)) = bad_key(layer, &mut Vec::new()) else {
+            found.push(Diagnostic::new(source, err.to_string()));
+            *layer = Value::Null;
+            break;
+        };
+        let message = format!("`{}`: {}", path.join("."), err);
+        found.push(Diagnostic::new(source, message).at_key(contents, &path));
+        remove(layer, &path);
+    }
+    found
+}
+
+fn prune_unknown(
+    layer: &mut Value,
+    known: &Value,
+    path: &mut Vec<String>,
+    report: &mut dyn FnMut(&[String], Vec<&str>),
+) {
+    let (Value::Mapping(layer), Value::Mapping(known)) = (layer, known) else {
+        return;
+    };
+    layer.retain(|key, value| {
+        let Some(key) = key.as_str() else {
+            return true;
+        };
+        path.push(key.to_string());
+        let keep = match known.get(key) {
+            Some(known) => {
+                prune_unknown(value, known, path, report);
+                true
+            }
+            None => {
+                report(path, known.keys().filter_map(Value::as_str).collect());
+                false
+            }
+        };
+        path.pop();
+        keep
+    });
+}
+
+/// The deepest key whose value alone fails to deserialize, and why.
+fn bad_key(layer: &Value, path: &mut Vec<String>) -> Option<(Vec<String>, serde_yml::Error)> {
+    let Value::Mapping(mapping) = layer else {
+        return None;
+    };
+    for (key, value) in mapping {
+        let Some(key) = key.as_str() else {
+            continue;
+        };
+        path.push(key.to_string());
+        let parts: Vec<&str> = path.iter().map(String::as_str).collect();
+        if let Err(err) = serde_yml::from_value::<Config>(nested(&parts, value.clone())) {
+            return Some(bad_key(value, path).unwrap_or_else(|| (path.clone(), err)));
+        }
+        path.pop();
+    }
+    None
+}
+
+fn remove(layer: &mut Value, path: &[String]) {
+    let Some((key, parents)) = path.split_last() else {
+        return;
+    };
+    let mut value = layer;
+    for parent in parents {
+        match value.get_mut(parent.as_str()) {
+            Some(child) => value = child,
+            None => return,
+        }
+    }
+    if let Value::Mapping(mapping) = value {
+        mapping.remove(key.as_str());
+    }
+}
+
+/// Byte offset where `path` is set, looking for each part after the one
+/// before it. Understands `key:`, `key =`, `"key":` and `[key]`.
+fn key_offset(contents: &str, path: &[String]) -> Option<usize> {
+    let mut from = 0;
+    let mut found = None;
+    for key in path {
+        let offset = contents[from..]
+            .match_indices(key.as_str())
+            .map(|(index, _)| from + index)
+            .find(|&index| is_key_at(contents, index, key))?;
+        found = Some(offset);
+        from = offset + key.len();
+    }
+    found
+}
+
+fn is_key_at(contents: &str, index: usize, key: &str) -> bool {
+    let before = contents[..index]
+        .trim_end_matches(['"', '\''])
+        .chars()
+        .next_back();
+    let after = contents[index + key.len()..]
+        .trim_start_matches(['"', '\''])
+        .trim_start_matches([' ', '\t'])
+        .chars()
+        .next();
+    matches!(
+        before,
+        None | Some('\n' | ' ' | '\t' | '{' | ',' | '[' | '.')
+    ) && matches!(after, Some(':' | '=' | ']' | '.'))
+}
+
+/// The candidate within a typo's reach of `key`, if any.
+fn closest<'a>(key: &str, candidates: Vec<&'a str>) -> Option<&'a str> {
+    let reach = (key.chars().count() / 3).max(1);
+    candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(key, candidate), candidate))
+        .filter(|(distance, _)| *distance <= reach)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Levenshtein distance between `a` and `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, b) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(a != *b);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parse;
+    use super::*;
+    use std::path::Path;
+
+    /// Parses `contents` as the file `name` and renders the error it reports.
+    fn parse_error(name: &str, contents: &str) -> (Option<(usize, usize)>, String) {
+        let err = parse::<Value>(Path::new(name), contents).expect_err("config should not parse");
+        let diagnostic = Diagnostic::from_parse_error(name, contents, &*err);
+        let position = diagnostic
+            .position
+            .as_ref()
+            .map(|position| (position.line, position.column));
+        (position, diagnostic.to_string())
+    }
+
+    #[test]
+    fn yaml_bad_indent() {
+        let (position, rendered) = parse_error("c.yml", "cpu:\n show_usage: true\n  bad: 1\n");
+        assert_eq!(position, Some((3, 6)));
+        assert!(rendered.starts_with("nekofetch: c.yml:3:6: "));
+        assert!(rendered.ends_with("3 |   bad: 1\n  |      ^"));
+    }
+
+    #[test]
+    fn yaml_eof() {
+        let (position, rendered) = parse_error("c.yml", "storage:\n  mounts: [\"/\",\n");
+        let (line, column) = position.expect("EOF error has a position");
+        assert!(line >= 2 && column >= 1);
+        assert!(rendered.contains(&format!("c.yml:{}:{}: ", line, column)));
+    }
+
+    #[test]
+    fn toml_bad_value() {
+        let (position, rendered) = parse_error("c.toml", "show_gpu = false\nshow_os = nope\n");
+        assert_eq!(position, Some((2, 11)));
+        assert!(rendered.contains("2 | show_os = nope"));
+    }
+
+    #[test]
+    fn toml_eof() {
+        let (position, _) = parse_error("c.toml", "[cpu]\nshow_usage = [\n");
+        let (line, column) = position.expect("EOF error has a position");
+        assert!(line >= 2 && column >= 1);
+    }
+
+    #[test]
+    fn json_bad_value() {
+        let (position, rendered) = parse_error("c.json", "{\n  \"show_gpu\": flase\n}\n");
+        assert_eq!(position.map(|(line, _)| line), Some(2));
+        assert!(rendered.contains("2 |   \"show_gpu\": flase"));
+    }
+
+    #[test]
+    fn json_eof_after_newline() {
+        // serde_json reports column 0 here.
+        let (position, rendered) = parse_error("c.json", "{\"show_gpu\": false,\n");
+        assert_eq!(position, Some((2, 1)));
+        assert!(rendered.starts_with("nekofetch: c.json:2:1: "));
+        assert!(rendered.ends_with("  | ^"));
+    }
+
+    #[test]
+    fn json_empty() {
+        let (position, rendered) = parse_error("c.json", "");
+        if let Some((line, column)) = position {
+            assert!(line >= 1 && column >= 1);
+        }
+        assert!(rendered.starts_with("nekofetch: c.json"));
+    }
+
+    #[test]
+    fn unknown_key_suggestion() {
+        let contents = "show_cpuu: true\ncpu:\n  show_usag: true\n";
+        let mut layer = parse::<Value>(Path::new("c.yml"), contents).unwrap();
+        let found = check_layer(
+            "c.yml",
+            Some(contents),
+            &mut layer,
+            &super::super::defaults(),
+        );
+        let rendered: Vec<String> = found.iter().map(ToString::to_string).collect();
+        assert_eq!(rendered.len(), 2);
+        assert!(rendered[0].starts_with(
+            "nekofetch: c.yml:1:1: unknown key `show_cpuu`, did you mean `show_cpu`?"
+        ));
+        assert!(rendered[1].starts_with(
+            "nekofetch: c.yml:3:3: unknown key `cpu.show_usag`, did you mean `cpu.show_usage`?"
+        ));
+    }
+
+    #[test]
+    fn bad_value_is_dropped() {
+        let contents = "show_os: false\nshow_kernel: nope\n";
+        let mut layer = parse::<Value>(Path::new("c.yml"), contents).unwrap();
+        let found = check_layer(
+            "c.yml",
+            Some(contents),
+            &mut layer,
+            &super::super::defaults(),
+        );
+        assert_eq!(found.len(), 1);
+        assert!(found[0]
+            .to_string()
+            .starts_with("nekofetch: c.yml:2:1: `show_kernel`"));
+        let config: Config = serde_yml::from_value(layer).unwrap();
+        assert!(!config.shows("os"));
+        assert!(config.shows("kernel"));
+    }
+}
@@ -0,0 +1,151 @@
+//! `nekofetch config init`: writes every known key with its default.
+//!
+//! Keys and defaults come from [`defaults`], the same tree the loader checks
+//! files against, and comments from [`key_doc`], so a new option shows up
+//! here as soon as it exists.
+
+use super::{defaults, find_in_dir, key_doc};
+use serde_yml::{Mapping, Value};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+const HEADER: &str = "\
+nekofetch config, written by `nekofetch config init`.
+Every key is listed with its default, so delete any you don't change.
+Commented-out keys are unset by default.";
+
+#[derive(Clone, Copy)]
+pub enum Format {
+    Yaml,
+    Toml,
+}
+
+impl Format {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "yaml" | "yml" => Some(Format::Yaml),
+            "toml" => Some(Format::Toml),
+            _ => None,
+        }
+    }
+
+    fn file_name(self) -> &'static str {
+        match self {
+            Format::Yaml => "config.yaml",
+            Format::Toml => "config.toml",
+        }
+    }
+}
+
+/// Writes the default config to the user config dir, returning its path.
+/// An existing file is only replaced with `force`.
+pub fn init(format: Format, force: bool) -> io::Result<PathBuf> {
+    let dir = dirs::config_dir()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no user config dir"))?
+        .join("nekofetch");
+    let path = dir.join(format.file_name());
+    if path.exists() && !force {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!(
+                "{} already exists, pass --force to overwrite it",
+                path.display()
+            ),
+        ));
+    }
+    fs::create_dir_all(&dir)?;
+    fs::write(&path, render(format))?;
+
+    if let Some(chosen) = find_in_dir(&dir, &mut Vec::new()).filter(|chosen| *chosen != path) {
+        eprintln!(
+            "nekofetch: {} takes precedence over the new file",
+            chosen.display()
+        );
+    }
+    Ok(path)
+}
+
+/// The whole config file, comments included.
+fn render(format: Format) -> String {
+    let mut out = String::new();
+    comment(&mut out, "", HEADER);
+    out.push('\n');
+    if let Value::Mapping(tree) = defaults() {
+        render_mapping(&mut out, format, &[], &tree);
+    }
+    out
+}
+
+fn render_mapping(out: &mut String, format: Format, path: &[&str], mapping: &Mapping) {
+    let indent = match format {
+        Format::Yaml => "  ".repeat(path.len()),
+        Format::Toml => String::new(),
+    };
+    let entries = || {
+        mapping
+            .iter()
+            .filter_map(|(key, value)| Some((key.as_str()?, value)))
+    };
+
+    // Plain keys first, as TOML ends a table at the next header.
+    for (key, value) in entries().filter(|(_, value)| !value.is_mapping()) {
+        let key_path = [path, &[key]].concat();
+        if let Some(doc) = key_doc(&key_path) {
+            comment(out, &indent, &doc);
+        }
+        // Unset keys and per-module timeouts would override something when
+        // set, so they are only shown.
+        let example = value.is_null() || (path == ["timeouts"] && key != "default");
+        let separator = match format {
+            Format::Yaml => ": ",
+            Format::Toml => " = ",
+        };
+        out.push_str(&format!(
+            "{}{}{}{}{}\n",
+            indent,
+            if example { "# " } else { "" },
+            key,
+            separator,
+            scalar(value)
+        ));
+    }
+
+    for (key, value) in entries() {
+        let Value::Mapping(child) = value else {
+            continue;
+        };
+        let key_path = [path, &[key]].concat();
+        out.push('\n');
+        if let Some(doc) = key_doc(&key_path) {
+            comment(out, &indent, &doc);
+        }
+        match format {
+            Format::Yaml => out.push_str(&format!("{}{}:\n", indent, key)),
+            Format::Toml => out.push_str(&format!("[{}]\n", key_path.join("."))),
+        }
+        render_mapping(out, format, &key_path, child);
+    }
+}
+
+fn comment(out: &mut String, indent: &str, text: &str) {
+    for line in text.lines() {
+        out.push_str(&format!("{}# {}\n", indent, line));
+    }
+}
+
+/// A value written inline. JSON string and array syntax is valid in both
+/// YAML and TOML.
+fn scalar(value: &Value) -> String {
+    match value {
+        Value::Null => "\"\"".to_string(),
+        Value::Bool(value) => value.to_string(),
+        Value::Number(value) => value.to_string(),
+        Value::String(value) => serde_json::to_string(value).unwrap_or_default(),
+        Value::Sequence(values) => {
+            let values: Vec<String> = values.iter().map(scalar).collect();
+            format!("[{}]", values.join(", "))
+        }
+        _ => String::new(),
+    }
+}
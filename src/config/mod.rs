@@ -0,0 +1,657 @@
+mod diagnostic;
+pub mod init;
+
+use crate::modules;
+pub use diagnostic::Diagnostic;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_yml::{Mapping, Value};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Module deadline used when neither the module nor `timeouts.default` sets one.
+const DEFAULT_TIMEOUT_MS: u64 = 1000;
+
+/// The merged config. Keys are described in `KEY_DOCS`.
+#[derive(Serialize, Deserialize, Default)]
+pub struct Config {
+    #[serde(default)]
+    pub root: Option<PathBuf>,
+    #[serde(default)]
+    pub timeouts: Timeouts,
+    #[serde(default)]
+    pub storage: StorageConfig,
+    #[serde(default)]
+    pub packages: PackagesConfig,
+    #[serde(default)]
+    pub os: OsConfig,
+    #[serde(default)]
+    pub network: NetworkConfig,
+    #[serde(default)]
+    pub cpu: CpuConfig,
+    #[serde(default)]
+    pub units: UnitsConfig,
+    #[serde(default)]
+    pub memory: MemoryConfig,
+    /// `show_<module>` toggles, keyed as written in the file. Modules
+    /// without an entry are shown.
+    #[serde(flatten)]
+    pub toggles: HashMap<String, bool>,
+}
+
+/// Set in a file as:
+///
+/// ```yaml
+/// timeouts:
+///   default: 500
+///   resolution: 2000
+/// ```
+#[derive(Serialize, Deserialize, Default)]
+pub struct Timeouts {
+    pub default: Option<u64>,
+    #[serde(flatten)]
+    pub modules: HashMap<String, u64>,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct StorageConfig {
+    pub mounts: Vec<String>,
+    pub exclude_mounts: Vec<String>,
+    pub fs_types: Vec<String>,
+    pub exclude_fs_types: Vec<String>,
+}
+
+impl StorageConfig {
+    pub fn allows(&self, mount: &str, fs_type: &str) -> bool {
+        let allowed = |allow: &[String], deny: &[String], value: &str| {
+            (allow.is_empty() || allow.iter().any(|a| a == value))
+                && !deny.iter().any(|d| d == value)
+        };
+        allowed(&self.mounts, &self.exclude_mounts, mount)
+            && allowed(&self.fs_types, &self.exclude_fs_types, fs_type)
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+pub struct PackagesConfig {
+    #[serde(flatten)]
+    pub sources: HashMap<String, bool>,
+}
+
+impl PackagesConfig {
+    /// Managers without an entry are counted.
+    pub fn enabled(&self, source: &str) -> bool {
+        self.sources.get(source).copied().unwrap_or(true)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct OsConfig {
+    pub fields: Vec<String>,
+}
+
+impl Default for OsConfig {
+    fn default() -> Self {
+        OsConfig {
+            fields: vec!["pretty_name".to_string()],
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkConfig {
+    pub show_mac: bool,
+    pub hide_loopback: bool,
+    pub hide_bridges: bool,
+    pub hide_link_local: bool,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        NetworkConfig {
+            show_mac: false,
+            hide_loopback: true,
+            hide_bridges: true,
+            hide_link_local: true,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct CpuConfig {
+    pub show_cores: bool,
+    pub show_hybrid: bool,
+    pub show_frequency: bool,
+    pub show_temperature: bool,
+    pub show_usage: bool,
+    pub usage_sample_ms: u64,
+}
+
+impl Default for CpuConfig {
+    fn default() -> Self {
+        CpuConfig {
+            show_cores: true,
+            show_hybrid: true,
+            show_frequency: true,
+            show_temperature: true,
+            show_usage: false,
+            usage_sample_ms: 200,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+pub struct MemoryConfig {
+    pub detailed: bool,
+    pub subtract_zfs_arc: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(default)]
+pub struct UnitsConfig {
+    pub system: UnitSystem,
+    pub scale: Scale,
+    pub precision: usize,
+    pub show_percent: bool,
+}
+
+impl Default for UnitsConfig {
+    fn default() -> Self {
+        UnitsConfig {
+            system: UnitSystem::Binary,
+            scale: Scale::Auto,
+            precision: 2,
+            show_percent: false,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum UnitSystem {
+    Binary,
+    Decimal,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[serde(rename_all = "lowercase")]
+pub enum Scale {
+    Auto,
+    Kilo,
+    Mega,
+    Giga,
+    Tera,
+}
+
+impl Timeouts {
+    pub fn for_module(&self, name: &str) -> u64 {
+        self.modules
+            .get(name)
+            .copied()
+            .or(self.default)
+            .unwrap_or(DEFAULT_TIMEOUT_MS)
+    }
+}
+
+impl Config {
+    /// Whether `module` is enabled, falling back to the module's own default.
+    pub fn shows(&self, module: &str) -> bool {
+        self.toggles
+            .get(&format!("show_{}", module))
+            .copied()
+            .unwrap_or_else(|| modules::find(module).is_none_or(|m| m.shown_by_default()))
+    }
+}
+
+/// Prefix of environment variables read as config overrides.
+const ENV_PREFIX: &str = "NEKOFETCH_";
+
+/// Keys that were renamed, with their current name. The old name still works.
+const RENAMED_KEYS: &[(&str, &str)] = &[("show_wm_theme", "show_theme")];
+
+/// Environment variable naming the config file, like `--config`.
+const CONFIG_ENV: &str = "NEKOFETCH_CONFIG";
+
+/// File names looked up in each config dir. Only the first one found is
+/// loaded, so a directory never mixes formats.
+const CONFIG_FILE_NAMES: [&str; 5] = [
+    "config.toml",
+    "config.yaml",
+    "config.yml",
+    "config.json",
+    // Name used before other formats were supported.
+    "nekofetch_config.yml",
+];
+
+/// Which config files to read.
+pub enum ConfigFiles {
+    /// `/etc/nekofetch`, then the user config dir. The current directory is
+    /// not searched, so the output doesn't depend on where nekofetch runs;
+    /// a project-local file has to be passed with `--config`.
+    Discover,
+    /// Only this file, from `--config` or `NEKOFETCH_CONFIG`.
+    Explicit(PathBuf),
+    /// No files at all, from `--no-config`.
+    None,
+}
+
+impl ConfigFiles {
+    /// Picks the files from `--no-config`, then `--config`, then
+    /// `NEKOFETCH_CONFIG`, discovering them if none is given.
+    pub fn from_args(no_config: bool, path: Option<PathBuf>) -> Self {
+        if no_config {
+            return ConfigFiles::None;
+        }
+        path.or_else(|| std::env::var_os(CONFIG_ENV).map(PathBuf::from))
+            .filter(|path| !path.as_os_str().is_empty())
+            .map_or(ConfigFiles::Discover, ConfigFiles::Explicit)
+    }
+
+    /// The files that will be loaded, lowest precedence first.
+    pub fn paths(&self, diagnostics: &mut Vec<Diagnostic>) -> Vec<PathBuf> {
+        match self {
+            ConfigFiles::Discover => {
+                let mut dirs = vec![PathBuf::from("/etc/nekofetch")];
+                dirs.extend(dirs::config_dir().map(|dir| dir.join("nekofetch")));
+                dirs.iter()
+                    .filter_map(|dir| find_in_dir(dir, diagnostics))
+                    .collect()
+            }
+            ConfigFiles::Explicit(path) => vec![path.clone()],
+            ConfigFiles::None => Vec::new(),
+        }
+    }
+}
+
+/// Builds the config from every layer, later ones overriding the keys they
+/// set in earlier ones: built-in defaults, the config `files`,
+/// `NEKOFETCH_*` variables and finally `overrides` from the command line, as
+/// `(dotted.key, value)` pairs.
+///
+/// A file that cannot be read or parsed is skipped, as are unknown keys and
+/// values of the wrong type; each is returned as a diagnostic alongside the
+/// config.
+pub fn load_config(
+    files: &ConfigFiles,
+    overrides: &[(String, String)],
+) -> (Config, Vec<Diagnostic>) {
+    let known = defaults();
+    let mut diagnostics = Vec::new();
+    let mut merged = Value::Mapping(Mapping::new());
+    for path in files.paths(&mut diagnostics) {
+        merge_file(&mut merged, &path, &known, &mut diagnostics);
+    }
+
+    let env = std::env::vars().filter_map(|(var, value)| {
+        if var == CONFIG_ENV {
+            return None;
+        }
+        let key = var
+            .strip_prefix(ENV_PREFIX)?
+            .to_lowercase()
+            .replace("__", ".");
+        Some((var, key, value))
+    });
+    let cli = overrides
+        .iter()
+        .map(|(key, value)| ("command line".to_string(), key.clone(), value.clone()));
+    for (source, key, value) in env.collect::<Vec<_>>().into_iter().chain(cli) {
+        let parts: Vec<&str> = key.split('.').collect();
+        let mut layer = nested(&parts, parse_scalar(&value));
+        rename_keys(&mut layer);
+        diagnostics.extend(diagnostic::check_layer(&source, None, &mut layer, &known));
+        // A layer that could not be salvaged is Null and sets nothing.
+        if !layer.is_null() {
+            merge(&mut merged, layer);
+        }
+    }
+
+    let config = serde_yml::from_value(merged).unwrap_or_else(|err| {
+        diagnostics.push(Diagnostic::new(
+            "config",
+            format!("layers do not combine, using defaults: {}", err),
+        ));
+        Config::default()
+    });
+    (config, diagnostics)
+}
+
+/// Merges the config file at `path` over `merged`, skipping it if it cannot
+/// be read or parsed.
+fn merge_file(merged: &mut Value, path: &Path, known: &Value, diagnostics: &mut Vec<Diagnostic>) {
+    let source = path.display().to_string();
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) => {
+            diagnostics.push(Diagnostic::new(source, format!("cannot read: {}", err)));
+            return;
+        }
+    };
+    match parse(path, &contents) {
+        // An empty file sets nothing.
+        Ok(Value::Null) => {}
+        Ok(mut layer) => {
+            rename_keys(&mut layer);
+            let problems = diagnostic::check_layer(&source, Some(&contents), &mut layer, known);
+            diagnostics.extend(problems);
+            // A file that could not be salvaged is Null, and would otherwise
+            // replace every earlier layer.
+            if !layer.is_null() {
+                merge(merged, layer);
+            }
+        }
+        Err(err) => {
+            diagnostics.push(Diagnostic::from_parse_error(&source, &contents, &*err));
+        }
+    }
+}
+
+/// Every known key with its default: `Config::default()` plus the module,
+/// timeout and package manager keys that only exist once a file sets them.
+pub fn defaults() -> Value {
+    let mut tree = serde_yml::to_value(Config::default()).unwrap_or(Value::Null);
+    let mut extra = Mapping::new();
+    let mut timeouts = Mapping::new();
+    timeouts.insert("default".into(), DEFAULT_TIMEOUT_MS.into());
+    for module in modules::registry() {
+        extra.insert(
+            format!("show_{}", module.name()).into(),
+            module.shown_by_default().into(),
+        );
+        timeouts.insert(module.name().into(), DEFAULT_TIMEOUT_MS.into());
+    }
+    let packages: Mapping = modules::package_managers()
+        .map(|name| (name.into(), true.into()))
+        .collect();
+    extra.insert("timeouts".into(), Value::Mapping(timeouts));
+    extra.insert("packages".into(), Value::Mapping(packages));
+    merge(&mut tree, Value::Mapping(extra));
+    tree
+}
+
+/// What each key does, by dotted path. This is the only description of the
+/// config: `config init` writes it into the generated file, so the fields
+/// above carry no doc comments of their own.
+const KEY_DOCS: &[(&str, &str)] = &[
+    (
+        "root",
+        "Directory treated as / when reading system files, for testing against a fixture\n\
+         tree. Empty means /.",
+    ),
+    (
+        "timeouts",
+        "How long each module may take, in milliseconds. Uncomment a module to\n\
+         give it its own deadline.",
+    ),
+    (
+        "timeouts.default",
+        "Deadline for modules without their own.",
+    ),
+    (
+        "storage",
+        "Which filesystems the storage module lists. Empty allow lists allow\n\
+         everything; deny lists win over allow lists.",
+    ),
+    (
+        "storage.mounts",
+        "Mount points to list, e.g. [\"/\", \"/home\"].",
+    ),
+    ("storage.exclude_mounts", "Mount points to leave out."),
+    (
+        "storage.fs_types",
+        "Filesystem types to list, e.g. [\"ext4\", \"btrfs\"].",
+    ),
+    ("storage.exclude_fs_types", "Filesystem types to leave out."),
+    (
+        "packages",
+        "Package managers the packages module counts. Set one to false to skip it.",
+    ),
+    ("os", "What the OS line shows."),
+    (
+        "os.fields",
+        "os-release fields the OS line is built from, joined with spaces. Any of\n\
+         name, pretty_name, id, id_like, version_id, variant and build_id.",
+    ),
+    ("network", "What the network module lists."),
+    ("network.show_mac", "Hardware address of each interface."),
+    ("network.hide_loopback", "Leave out lo."),
+    (
+        "network.hide_bridges",
+        "Leave out Docker, libvirt and other container/VM bridges and their veth pairs.",
+    ),
+    (
+        "network.hide_link_local",
+        "Leave out 169.254.0.0/16 and fe80::/10 addresses.",
+    ),
+    (
+        "cpu",
+        "Which details the CPU line includes after the model name.",
+    ),
+    (
+        "cpu.show_cores",
+        "Physical cores, threads and, on multi-socket boards, sockets.",
+    ),
+    (
+        "cpu.show_hybrid",
+        "Performance/efficiency core split on hybrid CPUs.",
+    ),
+    ("cpu.show_frequency", "Current and maximum clock speed."),
+    ("cpu.show_temperature", "Package temperature."),
+    (
+        "cpu.show_usage",
+        "Samples usage for usage_sample_ms, which delays the CPU line.",
+    ),
+    (
+        "cpu.usage_sample_ms",
+        "How long usage is sampled for, in milliseconds. This is added to the cpu\n\
+         module's timeout.",
+    ),
+    ("units", "How memory, swap and storage sizes are printed."),
+    (
+        "units.system",
+        "binary is KiB/MiB/GiB (powers of 1024), decimal is kB/MB/GB.",
+    ),
+    (
+        "units.scale",
+        "auto picks the largest unit that keeps the value at least 1; kilo, mega,\n\
+         giga or tera pin every size to that unit.",
+    ),
+    ("units.precision", "Digits after the decimal point."),
+    (
+        "units.show_percent",
+        "Append the used percentage to used / total pairs.",
+    ),
+    ("memory", "Memory and swap modules."),
+    (
+        "memory.detailed",
+        "Break memory down from /proc/meminfo and list swap devices.",
+    ),
+    (
+        "memory.subtract_zfs_arc",
+        "Count the ZFS ARC as available rather than used.",
+    ),
+];
+
+/// The comment for the key at `path`. Module toggles and package managers
+/// are described by their module, everything else by `KEY_DOCS`.
+pub fn key_doc(path: &[&str]) -> Option<String> {
+    match path {
+        [key] => {
+            if let Some(module) = key.strip_prefix("show_").and_then(modules::find) {
+                return Some(format!("{}: {}.", module.label(), module.description()));
+            }
+        }
+        ["packages", manager] => return Some(format!("Count {} packages.", manager)),
+        _ => {}
+    }
+    let path = path.join(".");
+    KEY_DOCS
+        .iter()
+        .find(|(key, _)| *key == path)
+        .map(|(_, doc)| doc.to_string())
+}
+
+/// The config file `dir` holds, warning about any others it shadows.
+fn find_in_dir(dir: &Path, diagnostics: &mut Vec<Diagnostic>) -> Option<PathBuf> {
+    let mut found = CONFIG_FILE_NAMES
+        .iter()
+        .map(|name| dir.join(name))
+        .filter(|path| path.is_file());
+    let chosen = found.next()?;
+    for shadowed in found {
+        diagnostics.push(Diagnostic::new(
+            shadowed.display().to_string(),
+            format!("ignored, {} takes precedence", chosen.display()),
+        ));
+    }
+    Some(chosen)
+}
+
+/// Deserializes `contents` by the file's extension, YAML unless it ends in
+/// `.toml` or `.json`.
+fn parse<T: DeserializeOwned>(path: &Path, contents: &str) -> Result<T, Box<dyn Error>> {
+    Ok(match path.extension().and_then(|ext| ext.to_str()) {
+        Some("toml") => toml::from_str(contents)?,
+        Some("json") => serde_json::from_str(contents)?,
+        _ => serde_yml::from_str(contents)?,
+    })
+}
+
+/// Moves keys set under an old name to their current one, unless the layer
+/// sets both.
+fn rename_keys(layer: &mut Value) {
+    let Value::Mapping(mapping) = layer else {
+        return;
+    };
+    for (old, new) in RENAMED_KEYS {
+        if let Some(value) = mapping.remove(*old) {
+            if !mapping.contains_key(*new) {
+                mapping.insert((*new).into(), value);
+            }
+        }
+    }
+}
+
+/// Reads an override value the way YAML would, so `false` is a bool and
+/// `500` a number, keeping anything unparsable as a string.
+fn parse_scalar(value: &str) -> Value {
+    serde_yml::from_str(value).unwrap_or_else(|_| Value::String(value.to_string()))
+}
+
+/// Turns `[cpu, show_usage]` and a value into `{cpu: {show_usage: value}}`.
+fn nested(path: &[&str], value: Value) -> Value {
+    path.iter().rev().fold(value, |value, part| {
+        let mut mapping = Mapping::new();
+        mapping.insert(Value::String(part.to_string()), value);
+        Value::Mapping(mapping)
+    })
+}
+
+/// Deep-merges `layer` into `base`: mappings are merged key by key, anything
+/// else in `layer` replaces what `base` had.
+fn merge(base: &mut Value, layer: Value) {
+    match (base, layer) {
+        (Value::Mapping(base), Value::Mapping(layer)) => {
+            for (key, value) in layer {
+                match base.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, layer) => *base = layer,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn overrides(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn renamed_key_still_works() {
+        let (config, diagnostics) = load_config(
+            &ConfigFiles::None,
+            &overrides(&[("show_wm_theme", "false")]),
+        );
+        assert!(diagnostics.is_empty());
+        assert!(!config.shows("theme"));
+    }
+
+    #[test]
+    fn malformed_layer_keeps_earlier_layers() {
+        let dir = std::env::temp_dir().join(format!("nekofetch-layers-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let system = dir.join("system.yaml");
+        fs::write(&system, "show_gpu: false\ncpu:\n  show_usage: true\n").unwrap();
+
+        let known = defaults();
+        for malformed in ["42\n", "show_gpu false\n"] {
+            let user = dir.join("user.yaml");
+            fs::write(&user, malformed).unwrap();
+            let mut merged = Value::Mapping(Mapping::new());
+            let mut diagnostics = Vec::new();
+            merge_file(&mut merged, &system, &known, &mut diagnostics);
+            merge_file(&mut merged, &user, &known, &mut diagnostics);
+
+            assert_eq!(diagnostics.len(), 1, "{:?}", malformed);
+            let config: Config = serde_yml::from_value(merged).unwrap();
+            assert!(!config.shows("gpu"));
+            assert!(config.cpu.show_usage);
+        }
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn current_key_wins_over_renamed_one() {
+        let mut layer: Value =
+            serde_yml::from_str("show_wm_theme: false\nshow_theme: true\n").unwrap();
+        rename_keys(&mut layer);
+        let config: Config = serde_yml::from_value(layer).unwrap();
+        assert!(config.shows("theme"));
+    }
+
+    /// Dotted paths of every leaf under `value`.
+    fn leaves(path: &[String], value: &Value, out: &mut Vec<Vec<String>>) {
+        match value {
+            Value::Mapping(mapping) => {
+                for (key, child) in mapping {
+                    if let Some(key) = key.as_str() {
+                        leaves(&[path, &[key.to_string()]].concat(), child, out);
+                    }
+                }
+            }
+            _ => out.push(path.to_vec()),
+        }
+    }
+
+    #[test]
+    fn every_key_is_documented() {
+        let mut paths = Vec::new();
+        leaves(&[], &defaults(), &mut paths);
+        let undocumented: Vec<String> = paths
+            .iter()
+            .map(|path| path.iter().map(String::as_str).collect::<Vec<_>>())
+            // Per-module timeouts share the table's comment.
+            .filter(|path| !(path.len() == 2 && path[0] == "timeouts" && path[1] != "default"))
+            .filter(|path| key_doc(path).is_none())
+            .map(|path| path.join("."))
+            .collect();
+        assert!(undocumented.is_empty(), "undocumented: {:?}", undocumented);
+    }
+}
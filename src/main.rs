@@ -1,16 +1,24 @@
 mod config;
-mod system_info;
+mod modules;
 mod ascii_art;
 mod display;
+mod units;
 
 use clap::{Arg, Command};
 use sysinfo::System;
-use std::collections::HashMap;
 use std::fs;
-use std::path::Path;
-use crate::config::Config;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use crate::config::{Config, ConfigFiles};
+use crate::modules::{Context, Module, ModuleError, Value};
 use colored::{Color, Colorize};
 
+/// Modules shown by `--mini`, in order.
+const MINI_MODULES: [&str; 3] = ["os", "cpu", "memory"];
+
+/// Modules that make up the `user@host` title instead of a line of their own.
+const TITLE_MODULES: [&str; 2] = ["username", "hostname"];
+
 fn main() {
     let (version, author, description) = get_metadata_from_cargo_toml().unwrap_or_else(|| (
         "Unknown".to_string(),
@@ -59,11 +67,105 @@ fn main() {
                 .help("Display terminal colors")
                 .action(clap::ArgAction::SetTrue),
         )
+        .arg(
+            Arg::new("show")
+                .long("show")
+                .value_name("MODULE")
+                .value_delimiter(',')
+                .help("Show a module, overriding the config")
+                .action(clap::ArgAction::Append),
+        )
+        .arg(
+            Arg::new("hide")
+                .long("hide")
+                .value_name("MODULE")
+                .value_delimiter(',')
+                .help("Hide a module, overriding the config")
+                .action(clap::ArgAction::Append),
+        )
+        .arg(
+            Arg::new("set")
+                .long("set")
+                .value_name("KEY=VALUE")
+                .value_parser(parse_override)
+                .help("Set a config key, e.g. cpu.show_usage=true")
+                .action(clap::ArgAction::Append),
+        )
+        .arg(
+            Arg::new("config")
+                .long("config")
+                .value_name("PATH")
+                .value_parser(clap::value_parser!(PathBuf))
+                .help("Read only this config file [env: NEKOFETCH_CONFIG]"),
+        )
+        .arg(
+            Arg::new("no_config")
+                .long("no-config")
+                .help("Ignore all config files")
+                .conflicts_with("config")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("print_config_path")
+                .long("print-config-path")
+                .help("Print the config files that would be read and exit")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("strict_config")
+                .long("strict-config")
+                .help("Exit with an error if the config has any problem")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .arg(
+            Arg::new("list_modules")
+                .long("list-modules")
+                .help("List the available modules and exit")
+                .action(clap::ArgAction::SetTrue),
+        )
+        .subcommand(
+            Command::new("config")
+                .about("Manage the config file")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("init")
+                        .about("Write a commented config with every option and its default")
+                        .arg(
+                            Arg::new("format")
+                                .long("format")
+                                .value_parser(["yaml", "toml"])
+                                .default_value("yaml")
+                                .help("File format to write"),
+                        )
+                        .arg(
+                            Arg::new("force")
+                                .long("force")
+                                .help("Overwrite an existing config file")
+                                .action(clap::ArgAction::SetTrue),
+                        ),
+                ),
+        )
         .get_matches();
 
+    if let Some(("config", config_matches)) = matches.subcommand() {
+        if let Some(("init", init_matches)) = config_matches.subcommand() {
+            let format = init_matches
+                .get_one::<String>("format")
+                .and_then(|name| config::init::Format::from_name(name))
+                .unwrap_or(config::init::Format::Yaml);
+            match config::init::init(format, init_matches.get_flag("force")) {
+                Ok(path) => println!("Wrote {}", path.display()),
+                Err(err) => {
+                    eprintln!("nekofetch: {}", err);
+                    std::process::exit(1);
+                }
+            }
+        }
+        return;
+    }
+
     if matches.get_flag("blahaj") {
         println!(
-            "{}",
             "⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⣀⣤⣤⡄⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀
 ⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⢀⣴⣿⣿⣿⡿⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀
 ⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⢀⣴⣿⣿⣿⣿⡿⠁⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀⠀
@@ -86,20 +188,47 @@ fn main() {
     }
 
 
+    if matches.get_flag("list_modules") {
+        for module in modules::registry() {
+            println!("{:<12} {}", module.name(), module.description());
+        }
+        return;
+    }
+
     let show_ascii = !matches.get_flag("no_ascii");
     let is_mini = matches.get_flag("mini");
     let use_caps = matches.get_flag("caps");
 
-    let config = config::load_config();
+    let files = ConfigFiles::from_args(
+        matches.get_flag("no_config"),
+        matches.get_one::<PathBuf>("config").cloned(),
+    );
+    if matches.get_flag("print_config_path") {
+        let mut diagnostics = Vec::new();
+        for path in files.paths(&mut diagnostics) {
+            println!("{}", path.display());
+        }
+        for diagnostic in &diagnostics {
+            eprintln!("{}", diagnostic);
+        }
+        return;
+    }
 
-    let mut sys = System::new_all();
-    sys.refresh_all();
+    let (config, diagnostics) = config::load_config(&files, &config_overrides(&matches));
+    for diagnostic in &diagnostics {
+        eprintln!("{}", diagnostic);
+    }
+    if matches.get_flag("strict_config") && !diagnostics.is_empty() {
+        std::process::exit(1);
+    }
 
-    // Gather system information
-    let info_map = system_info::gather_system_info(&sys);
+    // Only refresh what the enabled modules are going to read
+    let line_modules = line_modules(&config, is_mini);
+    let sys = System::new_with_specifics(modules::refresh_kind(&line_modules));
 
     // Build the information lines based on the config
-    let info = build_info_lines(&info_map, &config, use_caps, is_mini);
+    let ctx = Arc::new(Context::new(sys, config));
+    let info = build_info_lines(&ctx, &line_modules, use_caps, is_mini);
 
     // Get the ASCII art
     let ascii_art = if is_mini {
@@ -120,6 +249,35 @@ fn main() {
     display::display_info(&ascii_art, &info, show_colors);
 }
 
+/// `--show`, `--hide` and `--set` as config overrides, in that order.
+fn config_overrides(matches: &clap::ArgMatches) -> Vec<(String, String)> {
+    let toggles = |id: &str, value: &str| -> Vec<(String, String)> {
+        matches
+            .get_many::<String>(id)
+            .into_iter()
+            .flatten()
+            .map(|module| (format!("show_{}", module), value.to_string()))
+            .collect()
+    };
+    let mut overrides = toggles("show", "true");
+    overrides.extend(toggles("hide", "false"));
+    overrides.extend(
+        matches
+            .get_many::<(String, String)>("set")
+            .into_iter()
+            .flatten()
+            .cloned(),
+    );
+    overrides
+}
+
+fn parse_override(arg: &str) -> Result<(String, String), String> {
+    arg.split_once('=')
+        .map(|(key, value)| (key.trim().to_string(), value.to_string()))
+        .filter(|(key, _)| !key.is_empty())
+        .ok_or_else(|| format!("expected KEY=VALUE, got `{}`", arg))
+}
+
 fn get_metadata_from_cargo_toml() -> Option<(String, String, String)> {
     let cargo_toml_path = Path::new("Cargo.toml");
     if let Ok(contents) = fs::read_to_string(cargo_toml_path) {
@@ -152,112 +310,80 @@ fn get_metadata_from_cargo_toml() -> Option<(String, String, String)> {
 }
 
 
+/// Enabled modules that get a line of their own, in display order.
+fn line_modules(config: &Config, is_mini: bool) -> Vec<&'static dyn Module> {
+    let modules: Vec<&'static dyn Module> = if is_mini {
+        MINI_MODULES.iter().filter_map(|name| modules::find(name)).collect()
+    } else {
+        modules::registry()
+            .iter()
+            .copied()
+            .filter(|module| !TITLE_MODULES.contains(&module.name()))
+            .collect()
+    };
+    modules
+        .into_iter()
+        .filter(|module| config.shows(module.name()))
+        .collect()
+}
+
 fn build_info_lines(
-    info_map: &HashMap<String, String>,
-    config: &Config,
+    ctx: &Arc<Context>,
+    line_modules: &[&'static dyn Module],
     use_caps: bool,
     is_mini: bool,
 ) -> Vec<String> {
     let mut info = Vec::new();
 
-    if let Some(true) = config.show_username {
-        let username = &info_map["username"];
-        if let Some(true) = config.show_hostname {
-            let hostname = &info_map["hostname"];
-            info.push(format!(
-                "{}",
+    if ctx.config.shows("username") {
+        let username = collect_text(ctx, "username");
+        if ctx.config.shows("hostname") {
+            let hostname = collect_text(ctx, "hostname");
+            info.push(
                 format!("{}@{}", username, hostname)
                     .color(Color::BrightCyan)
                     .bold()
-            ));
+                    .to_string(),
+            );
         } else {
             info.push(username.color(Color::BrightCyan).bold().to_string());
         }
     }
 
-    if is_mini {
-        let mini_entries = vec!["os", "cpu", "memory"];
-        for key in mini_entries {
-            if let Some(true) = config_value(config, key) {
-                let value = &info_map[key];
-                let color = get_label_color(config, key);
-                let label = if use_caps {
-                    key.to_uppercase()
-                } else {
-                    key.to_string()
-                };
-                info.push(format!("{} {}", label.color(color).bold(), value));
-            }
-        }
-        return info;
-    }
-
-    let entries = vec![
-        ("os", "OS"),
-        ("hostname", "Host"),
-        ("kernel", "Kernel"),
-        ("uptime", "Uptime"),
-        ("packages", "Packages"),
-        ("shell", "Shell"),
-        ("resolution", "Resolution"),
-        ("de", "DE"),
-        ("wm", "WM"),
-        ("wm_theme", "WM Theme"),
-        ("terminal", "Terminal"),
-        ("cpu", "CPU"),
-        ("gpu", "GPU"),
-        ("memory", "Memory"),
-        ("storage", "Storage"),
-    ];
-
-    for (key, label) in entries {
-        if let Some(true) = config_value(config, key) {
-            let value = info_map
-                .get(key)
-                .unwrap_or(&"Unknown".to_string())
-                .clone();
-            let color = get_label_color(config, key);
-            let label = if use_caps {
-                label.to_uppercase()
-            } else {
-                label.to_lowercase()
-            };
-            info.push(format!("{} {}", label.color(color).bold(), value));
+    let results = modules::collect_all(ctx, line_modules);
+    for (module, result) in line_modules.iter().zip(results) {
+        // The mini layout labels lines with the module name itself.
+        let label = if is_mini { module.name() } else { module.label() };
+        let label = if use_caps {
+            label.to_uppercase()
+        } else {
+            label.to_lowercase()
+        };
+        let color = get_label_color(&ctx.config, module.name());
+        let lines = match result {
+            Ok(Value::Text(text)) => vec![text],
+            Ok(Value::Lines(lines)) => lines,
+            Err(ModuleError::NotPresent) => continue,
+            Err(err) => vec![err.to_string()],
+        };
+        for line in lines {
+            info.push(format!("{} {}", label.color(color).bold(), line));
         }
     }
 
     info
 }
 
-fn config_value(config: &Config, key: &str) -> Option<bool> {
-    match key {
-        "os" => config.show_os,
-        "host" => config.show_hostname,
-        "kernel" => config.show_kernel,
-        "uptime" => config.show_uptime,
-        "packages" => config.show_packages,
-        "shell" => config.show_shell,
-        "resolution" => config.show_resolution,
-        "de" => config.show_de,
-        "wm" => config.show_wm,
-        "wm_theme" => config.show_wm_theme,
-        "terminal" => config.show_terminal,
-        "cpu" => config.show_cpu,
-        "gpu" => config.show_gpu,
-        "memory" => config.show_memory,
-        "storage" => config.show_storage,
-        _ => Some(true),
+/// Collects a module as a single line, falling back to the error text.
+fn collect_text(ctx: &Context, name: &str) -> String {
+    match modules::find(name).map(|module| module.collect(ctx)) {
+        Some(Ok(Value::Text(text))) => text,
+        Some(Ok(Value::Lines(lines))) => lines.join(", "),
+        Some(Err(err)) => err.to_string(),
+        None => "Unknown".to_string(),
     }
 }
 
 fn get_label_color<'a>(_config: &'a Config, _key: &'a str) -> Color {
     Color::BrightCyan // Skyblue color
 }
-
-
-
-
-
-
-
-
@@ -0,0 +1,117 @@
+use super::{Context, Module, ModuleError, Value};
+use std::fs;
+use std::path::Path;
+
+pub struct Battery;
+
+impl Module for Battery {
+    fn name(&self) -> &'static str {
+        "battery"
+    }
+
+    fn label(&self) -> &'static str {
+        "Battery"
+    }
+
+    fn description(&self) -> &'static str {
+        "Charge, status, health and time left of each battery, and AC state"
+    }
+
+    fn collect(&self, ctx: &Context) -> Result<Value, ModuleError> {
+        let Ok(entries) = fs::read_dir(ctx.path("/sys/class/power_supply")) else {
+            return Err(ModuleError::NotPresent);
+        };
+        let mut supplies: Vec<_> = entries.flatten().map(|entry| entry.path()).collect();
+        supplies.sort();
+
+        let mut lines = Vec::new();
+        let mut ac_online = None;
+        for dir in supplies {
+            match read(&dir, "type").as_deref() {
+                Some("Battery") => {
+                    // Mice, keyboards and headsets report their batteries too.
+                    if read(&dir, "scope").as_deref() == Some("Device") {
+                        continue;
+                    }
+                    lines.extend(battery_line(&dir));
+                }
+                Some("Mains") => {
+                    let online = read(&dir, "online").as_deref() == Some("1");
+                    ac_online = Some(ac_online.unwrap_or(false) || online);
+                }
+                _ => {}
+            }
+        }
+
+        if lines.is_empty() {
+            return Err(ModuleError::NotPresent);
+        }
+        match ac_online {
+            Some(true) => lines.push("AC connected".to_string()),
+            Some(false) => lines.push("On battery power".to_string()),
+            None => {}
+        }
+        Ok(Value::Lines(lines))
+    }
+}
+
+fn battery_line(dir: &Path) -> Option<String> {
+    let name = dir.file_name()?.to_string_lossy().into_owned();
+    let capacity = read_u64(dir, "capacity")?;
+    let status = read(dir, "status").unwrap_or_else(|| "Unknown".to_string());
+
+    let mut details = vec![status.to_lowercase()];
+    if let Some(remaining) = time_remaining(dir, &status) {
+        details.push(remaining);
+    }
+    if let Some(health) = health(dir) {
+        details.push(format!("{}% health", health));
+    }
+    Some(format!("{}: {}% ({})", name, capacity, details.join(", ")))
+}
+
+/// Wear level: how much of the design capacity is left.
+fn health(dir: &Path) -> Option<u64> {
+    let (full, design) = read_u64(dir, "energy_full")
+        .zip(read_u64(dir, "energy_full_design"))
+        .or_else(|| read_u64(dir, "charge_full").zip(read_u64(dir, "charge_full_design")))?;
+    if design == 0 {
+        return None;
+    }
+    Some(full * 100 / design)
+}
+
+/// Time until empty or full at the current rate. Drivers report either
+/// energy (µWh, µW) or charge (µAh, µA).
+fn time_remaining(dir: &Path, status: &str) -> Option<String> {
+    let (now, full, rate) = read_u64(dir, "energy_now")
+        .zip(read_u64(dir, "energy_full"))
+        .zip(read_u64(dir, "power_now"))
+        .or_else(|| {
+            read_u64(dir, "charge_now")
+                .zip(read_u64(dir, "charge_full"))
+                .zip(read_u64(dir, "current_now"))
+        })
+        .map(|((now, full), rate)| (now, full, rate))?;
+    if rate == 0 {
+        return None;
+    }
+
+    let (left, suffix) = match status {
+        "Discharging" => (now, "remaining"),
+        "Charging" => (full.saturating_sub(now), "until full"),
+        _ => return None,
+    };
+    let minutes = left * 60 / rate;
+    Some(format!("{}h {}m {}", minutes / 60, minutes % 60, suffix))
+}
+
+fn read(dir: &Path, file: &str) -> Option<String> {
+    fs::read_to_string(dir.join(file))
+        .ok()
+        .map(|contents| contents.trim().to_string())
+}
+
+fn read_u64(dir: &Path, file: &str) -> Option<u64> {
+    read(dir, file)?.parse().ok()
+}
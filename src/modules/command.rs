@@ -0,0 +1,88 @@
+//! Running external programs from modules without letting them hang.
+
+use std::io::Read;
+use std::process::{Child, Command, Stdio};
+use std::sync::{Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How often a running child is polled for exit.
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+
+/// Children that are still running, so a module that timed out can't leave
+/// them behind. `None` once [`kill_running`] has run and no more may start.
+static RUNNING: Mutex<Option<Vec<Child>>> = Mutex::new(Some(Vec::new()));
+
+fn running() -> MutexGuard<'static, Option<Vec<Child>>> {
+    RUNNING.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Runs `command` and returns its stdout if it exits successfully within
+/// `timeout`. A child still running at the deadline, or when
+/// [`kill_running`] is called, is killed.
+pub fn stdout(command: &mut Command, timeout: Duration) -> Option<String> {
+    let mut children = running();
+    // Collection is over, anything started now would outlive nekofetch.
+    children.as_ref()?;
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+
+    // Drain stdout on the side so a chatty child can't block on a full pipe.
+    let mut pipe = child.stdout.take()?;
+    let reader = thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = pipe.read_to_end(&mut buf);
+        buf
+    });
+    let id = child.id();
+    children.as_mut()?.push(child);
+    drop(children);
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        let mut children = running();
+        // Gone from the list means `kill_running` already killed it.
+        let index = children
+            .as_ref()?
+            .iter()
+            .position(|child| child.id() == id)?;
+        let list = children.as_mut()?;
+        match list[index].try_wait() {
+            Ok(Some(status)) => {
+                // Already reaped, this just drops it from the list.
+                let _ = list.swap_remove(index).wait();
+                break status;
+            }
+            Ok(None) if Instant::now() < deadline => {
+                drop(children);
+                thread::sleep(POLL_INTERVAL);
+            }
+            _ => {
+                let mut child = list.swap_remove(index);
+                let _ = child.kill();
+                let _ = child.wait();
+                return None;
+            }
+        }
+    };
+
+    let buf = reader.join().ok()?;
+    if !status.success() {
+        return None;
+    }
+    Some(String::from_utf8_lossy(&buf).into_owned())
+}
+
+/// Kills every child still running and stops new ones from starting. Called
+/// once collection is over, as modules that timed out may still be waiting
+/// on a child when nekofetch exits.
+pub fn kill_running() {
+    for mut child in running().take().into_iter().flatten() {
+        let _ = child.kill();
+        let _ = child.wait();
+    }
+}
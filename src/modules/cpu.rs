@@ -0,0 +1,251 @@
+use super::{Context, Module, ModuleError, Refresh, Value};
+use crate::config::{Config, CpuConfig};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+use sysinfo::{Components, System, MINIMUM_CPU_UPDATE_INTERVAL};
+
+/// Sensor labels that carry the package temperature, best first: Intel,
+/// AMD (k10temp, zenpower) and common SoC thermal zones.
+const TEMPERATURE_LABELS: &[&str] = &["Package id", "Tctl", "Tdie", "cpu", "CPU", "soc"];
+
+pub struct Cpu;
+
+impl Module for Cpu {
+    fn name(&self) -> &'static str {
+        "cpu"
+    }
+
+    fn label(&self) -> &'static str {
+        "CPU"
+    }
+
+    fn description(&self) -> &'static str {
+        "Processor model, topology, frequency, temperature and usage"
+    }
+
+    fn refreshes(&self) -> &'static [Refresh] {
+        &[Refresh::Cpu]
+    }
+
+    fn waits(&self, config: &Config) -> Duration {
+        if config.cpu.show_usage {
+            usage_sample(&config.cpu)
+        } else {
+            Duration::ZERO
+        }
+    }
+
+    fn collect(&self, ctx: &Context) -> Result<Value, ModuleError> {
+        let options = &ctx.config.cpu;
+        let cpus = ctx.sys.cpus();
+        let cpu_brand = cpus
+            .first()
+            .map(|cpu| cpu.brand().trim().to_string())
+            .ok_or(ModuleError::Unavailable)?;
+        let mut line = cpu_brand;
+
+        if options.show_cores {
+            let topology = Topology::from_sysfs(ctx).unwrap_or_else(|| Topology {
+                sockets: 1,
+                cores: ctx.sys.physical_core_count().unwrap_or(cpus.len()),
+                threads: cpus.len(),
+                hybrid: None,
+            });
+            line.push_str(&format!(" ({})", topology.describe(options.show_hybrid)));
+        }
+
+        if options.show_frequency {
+            let current = cpus.iter().map(|cpu| cpu.frequency()).max().unwrap_or(0);
+            let max = max_frequency(ctx);
+            let ghz = |mhz: u64| format!("{:.2}", mhz as f64 / 1000.0);
+            match (current, max) {
+                (0, Some(max)) => line.push_str(&format!(" @ {} GHz", ghz(max))),
+                (current, Some(max)) if current < max => {
+                    line.push_str(&format!(" @ {}/{} GHz", ghz(current), ghz(max)))
+                }
+                (0, None) => {}
+                (current, _) => line.push_str(&format!(" @ {} GHz", ghz(current))),
+            }
+        }
+
+        let mut extras = Vec::new();
+        if options.show_temperature {
+            extras.extend(temperature().map(|celsius| format!("{:.0}°C", celsius)));
+        }
+        if options.show_usage {
+            extras.push(format!("{:.0}% used", usage(usage_sample(options))));
+        }
+        if !extras.is_empty() {
+            line.push_str(&format!(" [{}]", extras.join(", ")));
+        }
+        Ok(line.into())
+    }
+}
+
+struct Topology {
+    sockets: usize,
+    cores: usize,
+    threads: usize,
+    /// Performance and efficiency core counts on hybrid CPUs.
+    hybrid: Option<(usize, usize)>,
+}
+
+impl Topology {
+    /// Reads package and core ids of every online CPU from
+    /// `/sys/devices/system/cpu`.
+    fn from_sysfs(ctx: &Context) -> Option<Topology> {
+        let cpu_dir = ctx.path("/sys/devices/system/cpu");
+        let online = fs::read_to_string(cpu_dir.join("online"))
+            .ok()
+            .map(|list| parse_cpu_list(&list));
+
+        let mut ids = Vec::new();
+        for entry in fs::read_dir(&cpu_dir).ok()?.flatten() {
+            let name = entry.file_name();
+            let Some(index) = name
+                .to_str()
+                .and_then(|name| name.strip_prefix("cpu"))
+                .and_then(|index| index.parse::<usize>().ok())
+            else {
+                continue;
+            };
+            if online
+                .as_ref()
+                .is_some_and(|online| !online.contains(&index))
+            {
+                continue;
+            }
+            let topology = entry.path().join("topology");
+            let package = read_usize(&topology.join("physical_package_id")).unwrap_or(0);
+            let core = read_usize(&topology.join("core_id")).unwrap_or(index);
+            ids.push((index, package, core));
+        }
+        if ids.is_empty() {
+            return None;
+        }
+
+        let sockets: HashSet<usize> = ids.iter().map(|&(_, package, _)| package).collect();
+        let cores: HashSet<(usize, usize)> = ids
+            .iter()
+            .map(|&(_, package, core)| (package, core))
+            .collect();
+        Some(Topology {
+            sockets: sockets.len(),
+            cores: cores.len(),
+            threads: ids.len(),
+            hybrid: hybrid_split(ctx, &cpu_dir, &ids),
+        })
+    }
+
+    fn describe(&self, show_hybrid: bool) -> String {
+        let mut parts = Vec::new();
+        if self.sockets > 1 {
+            parts.push(format!("{} sockets", self.sockets));
+        }
+        parts.push(format!("{}C/{}T", self.cores, self.threads));
+        if let (true, Some((performance, efficiency))) = (show_hybrid, self.hybrid) {
+            parts.push(format!("{}P + {}E", performance, efficiency));
+        }
+        parts.join(", ")
+    }
+}
+
+/// Splits cores into performance and efficiency ones. Intel hybrid parts
+/// list them under the `cpu_core`/`cpu_atom` PMUs; ARM big.LITTLE SoCs give
+/// each CPU a `cpu_capacity`, highest on the big cores.
+fn hybrid_split(
+    ctx: &Context,
+    cpu_dir: &Path,
+    ids: &[(usize, usize, usize)],
+) -> Option<(usize, usize)> {
+    let count_cores = |cpus: &HashSet<usize>| {
+        ids.iter()
+            .filter(|(index, _, _)| cpus.contains(index))
+            .map(|&(_, package, core)| (package, core))
+            .collect::<HashSet<_>>()
+            .len()
+    };
+
+    let pmu = |name: &str| {
+        fs::read_to_string(ctx.path(&format!("/sys/devices/{}/cpus", name)))
+            .ok()
+            .map(|list| parse_cpu_list(&list))
+    };
+    if let (Some(performance), Some(efficiency)) = (pmu("cpu_core"), pmu("cpu_atom")) {
+        return Some((count_cores(&performance), count_cores(&efficiency)));
+    }
+
+    let capacities: Vec<(usize, usize)> = ids
+        .iter()
+        .filter_map(|&(index, _, _)| {
+            let path = cpu_dir.join(format!("cpu{}/cpu_capacity", index));
+            Some((index, read_usize(&path)?))
+        })
+        .collect();
+    let top = capacities.iter().map(|&(_, capacity)| capacity).max()?;
+    let (performance, efficiency): (Vec<_>, Vec<_>) = capacities
+        .iter()
+        .partition(|&&(_, capacity)| capacity == top);
+    if efficiency.is_empty() {
+        return None;
+    }
+    let indices = |cpus: Vec<&(usize, usize)>| cpus.iter().map(|&&(index, _)| index).collect();
+    Some((
+        count_cores(&indices(performance)),
+        count_cores(&indices(efficiency)),
+    ))
+}
+
+/// Highest `cpuinfo_max_freq` over all CPUs, in MHz.
+fn max_frequency(ctx: &Context) -> Option<u64> {
+    let entries = fs::read_dir(ctx.path("/sys/devices/system/cpu")).ok()?;
+    entries
+        .flatten()
+        .filter_map(|entry| read_usize(&entry.path().join("cpufreq/cpuinfo_max_freq")))
+        .max()
+        .map(|khz| khz as u64 / 1000)
+}
+
+fn temperature() -> Option<f32> {
+    let components = Components::new_with_refreshed_list();
+    TEMPERATURE_LABELS.iter().find_map(|label| {
+        components
+            .iter()
+            .find(|component| component.label().contains(label))
+            .map(|component| component.temperature())
+            .filter(|celsius| celsius.is_finite() && *celsius > 0.0)
+    })
+}
+
+/// How long usage is sampled for; sysinfo needs at least its update interval.
+fn usage_sample(options: &CpuConfig) -> Duration {
+    Duration::from_millis(options.usage_sample_ms).max(MINIMUM_CPU_UPDATE_INTERVAL)
+}
+
+/// Overall CPU usage over `sample`.
+fn usage(sample: Duration) -> f32 {
+    let mut sys = System::new();
+    sys.refresh_cpu_usage();
+    thread::sleep(sample);
+    sys.refresh_cpu_usage();
+    sys.global_cpu_usage()
+}
+
+/// Parses kernel CPU lists such as `0-3,8,10-11`.
+fn parse_cpu_list(list: &str) -> HashSet<usize> {
+    let mut cpus = HashSet::new();
+    for range in list.trim().split(',').filter(|range| !range.is_empty()) {
+        let (start, end) = range.split_once('-').unwrap_or((range, range));
+        if let (Ok(start), Ok(end)) = (start.parse::<usize>(), end.parse::<usize>()) {
+            cpus.extend(start..=end);
+        }
+    }
+    cpus
+}
+
+fn read_usize(path: &Path) -> Option<usize> {
+    fs::read_to_string(path).ok()?.trim().parse().ok()
+}
@@ -0,0 +1,30 @@
+use super::{Context, Module, ModuleError, Value};
+use std::env;
+
+pub struct De;
+
+impl Module for De {
+    fn name(&self) -> &'static str {
+        "de"
+    }
+
+    fn label(&self) -> &'static str {
+        "DE"
+    }
+
+    fn description(&self) -> &'static str {
+        "Desktop environment"
+    }
+
+    fn collect(&self, _ctx: &Context) -> Result<Value, ModuleError> {
+        if cfg!(target_os = "linux") {
+            env::var("XDG_CURRENT_DESKTOP")
+                .map(Value::Text)
+                .map_err(|_| ModuleError::Unavailable)
+        } else if cfg!(target_os = "macos") {
+            Ok("Aqua".to_string().into())
+        } else {
+            Err(ModuleError::Unavailable)
+        }
+    }
+}
@@ -0,0 +1,56 @@
+//! Firmware-provided machine identity from `/sys/devices/virtual/dmi/id`.
+
+use super::Context;
+use std::fs;
+
+/// Values vendors leave in unset DMI fields, compared case-insensitively.
+const PLACEHOLDERS: &[&str] = &[
+    "to be filled by o.e.m.",
+    "default string",
+    "system product name",
+    "system manufacturer",
+    "system version",
+    "base board product name",
+    "base board manufacturer",
+    "not applicable",
+    "not specified",
+    "not available",
+    "none",
+    "o.e.m.",
+    "oem",
+    "type1productconfigid",
+    "123456789",
+    "0123456789",
+    "x.x",
+];
+
+/// Reads one DMI field, e.g. `sys_vendor` or `product_name`, skipping
+/// placeholder values.
+pub fn read(ctx: &Context, field: &str) -> Option<String> {
+    ["/sys/devices/virtual/dmi/id", "/sys/class/dmi/id"]
+        .iter()
+        .find_map(|dir| fs::read_to_string(ctx.path(&format!("{}/{}", dir, field))).ok())
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty() && !is_placeholder(value))
+}
+
+/// Board model from the devicetree, as set on ARM boards like the Raspberry Pi.
+pub fn devicetree_model(ctx: &Context) -> Option<String> {
+    [
+        "/proc/device-tree/model",
+        "/sys/firmware/devicetree/base/model",
+    ]
+    .iter()
+    .find_map(|path| fs::read(ctx.path(path)).ok())
+    .map(|raw| {
+        String::from_utf8_lossy(&raw)
+            .trim_matches(|c: char| c == '\0' || c.is_whitespace())
+            .to_string()
+    })
+    .filter(|model| !model.is_empty())
+}
+
+fn is_placeholder(value: &str) -> bool {
+    let value = value.to_lowercase();
+    PLACEHOLDERS.contains(&value.as_str())
+}
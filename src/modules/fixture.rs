@@ -0,0 +1,65 @@
+//! Throwaway root directories for testing modules against fake system files.
+
+use super::Context;
+use crate::config::Config;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use sysinfo::System;
+
+/// A temporary directory used as the config `root`, removed on drop.
+pub struct Fixture {
+    root: PathBuf,
+}
+
+impl Fixture {
+    pub fn new() -> Self {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let root = std::env::temp_dir().join(format!(
+            "nekofetch-fixture-{}-{}",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::create_dir_all(&root).expect("create fixture root");
+        Fixture { root }
+    }
+
+    /// Writes `contents` to the absolute system `path` inside the fixture.
+    pub fn file(&self, path: &str, contents: impl AsRef<[u8]>) -> &Self {
+        let path = self.root.join(path.trim_start_matches('/'));
+        fs::create_dir_all(path.parent().expect("file has a parent")).expect("create parent");
+        fs::write(path, contents).expect("write fixture file");
+        self
+    }
+
+    /// Creates the absolute system directory `path` inside the fixture.
+    pub fn dir(&self, path: &str) -> &Self {
+        fs::create_dir_all(self.root.join(path.trim_start_matches('/'))).expect("create dir");
+        self
+    }
+
+    /// Creates a symlink at the absolute system `path` inside the fixture,
+    /// pointing at `target` as written.
+    #[cfg(unix)]
+    pub fn symlink(&self, path: &str, target: &str) -> &Self {
+        let path = self.root.join(path.trim_start_matches('/'));
+        fs::create_dir_all(path.parent().expect("link has a parent")).expect("create parent");
+        std::os::unix::fs::symlink(target, path).expect("create fixture symlink");
+        self
+    }
+
+    /// A context reading system files from the fixture.
+    pub fn context(&self) -> Context {
+        let config = Config {
+            root: Some(self.root.clone()),
+            ..Config::default()
+        };
+        Context::new(System::new(), config)
+    }
+}
+
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
@@ -0,0 +1,265 @@
+use super::{command, Context, Module, ModuleError, Value};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+mod pci_ids;
+
+/// PCI base class of display controllers.
+const DISPLAY_CLASS: u32 = 0x03;
+
+/// Vendors whose GPUs only exist inside virtual machines.
+const VIRTUAL_VENDORS: &[u16] = &[0x1234, 0x15ad, 0x1af4, 0x1b36, 0x1414, 0x80ee];
+
+pub struct Gpu;
+
+impl Module for Gpu {
+    fn name(&self) -> &'static str {
+        "gpu"
+    }
+
+    fn label(&self) -> &'static str {
+        "GPU"
+    }
+
+    fn description(&self) -> &'static str {
+        "Graphics adapters with their kernel driver"
+    }
+
+    fn collect(&self, ctx: &Context) -> Result<Value, ModuleError> {
+        let gpus = if cfg!(target_os = "linux") {
+            get_pci_gpus(ctx)
+        } else {
+            get_gpu_info(ctx).into_iter().collect()
+        };
+        if gpus.is_empty() {
+            return Err(ModuleError::Unavailable);
+        }
+        Ok(Value::Lines(gpus))
+    }
+}
+
+enum GpuKind {
+    Integrated,
+    Discrete,
+    Virtual,
+}
+
+impl fmt::Display for GpuKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GpuKind::Integrated => write!(f, "integrated"),
+            GpuKind::Discrete => write!(f, "discrete"),
+            GpuKind::Virtual => write!(f, "virtual"),
+        }
+    }
+}
+
+/// Lists display controllers from sysfs, one line per GPU.
+fn get_pci_gpus(ctx: &Context) -> Vec<String> {
+    let Ok(entries) = fs::read_dir(ctx.path("/sys/bus/pci/devices")) else {
+        return Vec::new();
+    };
+    let mut devices: Vec<_> = entries.flatten().map(|entry| entry.path()).collect();
+    devices.sort();
+
+    let mut pci_ids = None;
+    let mut gpus = Vec::new();
+    for dir in devices {
+        let Some(class) = read_hex(&dir.join("class")) else {
+            continue;
+        };
+        if class >> 16 != DISPLAY_CLASS {
+            continue;
+        }
+        let (Some(vendor), Some(device)) =
+            (read_hex(&dir.join("vendor")), read_hex(&dir.join("device")))
+        else {
+            continue;
+        };
+        let (vendor, device) = (vendor as u16, device as u16);
+
+        let db = pci_ids.get_or_insert_with(|| pci_ids::load(ctx));
+        let name = gpu_name(db, vendor, device);
+        let driver = fs::read_link(dir.join("driver"))
+            .ok()
+            .and_then(|link| Some(link.file_name()?.to_string_lossy().into_owned()));
+
+        let details: Vec<String> = gpu_kind(&dir, vendor, device)
+            .map(|kind| kind.to_string())
+            .into_iter()
+            .chain(driver)
+            .collect();
+        if details.is_empty() {
+            gpus.push(name);
+        } else {
+            gpus.push(format!("{} ({})", name, details.join(", ")));
+        }
+    }
+    gpus
+}
+
+/// Short marketing name, e.g. `NVIDIA GeForce RTX 4090` out of
+/// `NVIDIA Corporation` / `AD102 [GeForce RTX 4090]`.
+fn gpu_name(db: &str, vendor: u16, device: u16) -> String {
+    let (vendor_name, device_name) = pci_ids::lookup(db, vendor, device)
+        .unwrap_or_else(|| (format!("Vendor {:04x}", vendor), None));
+    let vendor_name = match vendor {
+        0x1002 => "AMD".to_string(),
+        0x10de => "NVIDIA".to_string(),
+        0x8086 => "Intel".to_string(),
+        _ => vendor_name,
+    };
+    let device_name = match device_name {
+        Some(name) => match (name.rfind('['), name.rfind(']')) {
+            (Some(start), Some(end)) if start < end => name[start + 1..end].to_string(),
+            _ => name,
+        },
+        None => format!("Device {:04x}", device),
+    };
+    format!("{} {}", vendor_name, device_name)
+}
+
+fn gpu_kind(dir: &Path, vendor: u16, device: u16) -> Option<GpuKind> {
+    if VIRTUAL_VENDORS.contains(&vendor) {
+        return Some(GpuKind::Virtual);
+    }
+    match vendor {
+        0x10de => Some(GpuKind::Discrete),
+        // Iris Xe MAX, Arc Alchemist (desktop and mobile) and Battlemage;
+        // everything else Intel is an iGPU.
+        0x8086
+            if matches!(
+                device,
+                0x4905..=0x4909 | 0x5690..=0x5698 | 0x56a0..=0x56c1 | 0xe202..=0xe212
+            ) =>
+        {
+            Some(GpuKind::Discrete)
+        }
+        0x8086 => Some(GpuKind::Integrated),
+        0x1002 => amd_kind(dir),
+        _ => None,
+    }
+}
+
+/// amdgpu only creates `mem_info_vram_vendor` for cards with their own VRAM
+/// chips and hides `pcie_bw` on APUs, so either one means a dedicated card.
+/// The VRAM size can't tell them apart: APU carve-outs go up to 16 GiB.
+fn amd_kind(dir: &Path) -> Option<GpuKind> {
+    if dir.join("mem_info_vram_vendor").exists() || dir.join("pcie_bw").exists() {
+        return Some(GpuKind::Discrete);
+    }
+    // Older APUs sit right on the root bus, e.g. `0000:00:01.0`.
+    let on_root_bus = dir
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|address| address.split(':').nth(1) == Some("00"));
+    (on_root_bus || dir.join("mem_info_vram_total").exists()).then_some(GpuKind::Integrated)
+}
+
+fn read_hex(path: &Path) -> Option<u32> {
+    let contents = fs::read_to_string(path).ok()?;
+    u32::from_str_radix(contents.trim().trim_start_matches("0x"), 16).ok()
+}
+
+/// Asks the OS tools for the primary GPU where there is no sysfs.
+fn get_gpu_info(ctx: &Context) -> Option<String> {
+    let timeout = ctx.timeout("gpu");
+    if cfg!(target_os = "macos") {
+        if let Some(stdout) = command::stdout(
+            Command::new("system_profiler").arg("SPDisplaysDataType"),
+            timeout,
+        ) {
+            for line in stdout.lines() {
+                if line.trim().starts_with("Chipset Model:") {
+                    let gpu = line.trim().replace("Chipset Model:", "").trim().to_string();
+                    return Some(gpu);
+                }
+            }
+        }
+    } else if cfg!(target_os = "windows") {
+        // For Windows, use the 'wmic' command
+        if let Some(stdout) = command::stdout(
+            Command::new("wmic").args(["path", "win32_VideoController", "get", "name"]),
+            timeout,
+        ) {
+            let lines: Vec<&str> = stdout.lines().collect();
+            if lines.len() >= 2 {
+                return Some(lines[1].trim().to_string());
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::fixture::Fixture;
+
+    /// The kind of an AMD GPU at `address` with the given sysfs attributes.
+    fn amd(address: &str, attributes: &[&str]) -> Option<String> {
+        let fixture = Fixture::new();
+        let dir = format!("/sys/bus/pci/devices/{}", address);
+        fixture.dir(&dir);
+        for attribute in attributes {
+            fixture.file(&format!("{}/{}", dir, attribute), "0\n");
+        }
+        gpu_kind(&fixture.context().path(&dir), 0x1002, 0x1234).map(|kind| kind.to_string())
+    }
+
+    #[test]
+    fn small_amd_card_is_discrete() {
+        // An RX 550 with 2 GiB of GDDR5.
+        let kind = amd(
+            "0000:03:00.0",
+            &["mem_info_vram_total", "mem_info_vram_vendor", "pcie_bw"],
+        );
+        assert_eq!(kind.as_deref(), Some("discrete"));
+    }
+
+    #[test]
+    fn amd_apu_with_large_carve_out_is_integrated() {
+        let kind = amd("0000:e5:00.0", &["mem_info_vram_total"]);
+        assert_eq!(kind.as_deref(), Some("integrated"));
+    }
+
+    #[test]
+    fn old_amd_apu_on_root_bus_is_integrated() {
+        assert_eq!(amd("0000:00:01.0", &[]).as_deref(), Some("integrated"));
+    }
+
+    #[test]
+    fn amd_without_amdgpu_is_unknown() {
+        assert_eq!(amd("0000:01:00.0", &[]), None);
+    }
+
+    #[test]
+    fn names_from_pci_ids() {
+        let db = "10de  NVIDIA Corporation\n\t2684  AD102 [GeForce RTX 4090]\n\
+                  1af4  Red Hat, Inc.\n\t1050  Virtio 1.0 GPU\n";
+        assert_eq!(gpu_name(db, 0x10de, 0x2684), "NVIDIA GeForce RTX 4090");
+        assert_eq!(gpu_name(db, 0x1af4, 0x1050), "Red Hat, Inc. Virtio 1.0 GPU");
+        assert_eq!(gpu_name(db, 0x10de, 0x2782), "NVIDIA Device 2782");
+    }
+
+    #[test]
+    fn names_without_pci_ids() {
+        assert_eq!(gpu_name("", 0x10de, 0x2684), "NVIDIA Device 2684");
+        assert_eq!(gpu_name("", 0x1af4, 0x1050), "Vendor 1af4 Device 1050");
+    }
+
+    #[test]
+    fn intel_arc_ids() {
+        let dir = Path::new("/nonexistent");
+        for device in [0x4905, 0x5690, 0x5694, 0x5698, 0x56a0, 0x56c1, 0xe20b] {
+            let kind = gpu_kind(dir, 0x8086, device).map(|kind| kind.to_string());
+            assert_eq!(kind.as_deref(), Some("discrete"), "device {:04x}", device);
+        }
+        for device in [0x46a6, 0x9a49, 0x7d55] {
+            let kind = gpu_kind(dir, 0x8086, device).map(|kind| kind.to_string());
+            assert_eq!(kind.as_deref(), Some("integrated"), "device {:04x}", device);
+        }
+    }
+}
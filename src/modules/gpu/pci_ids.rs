@@ -0,0 +1,46 @@
+//! Vendor and device names from the `pci.ids` database.
+
+use crate::modules::Context;
+use std::fs;
+
+/// Where distros install `pci.ids`, tried in order.
+const PCI_IDS_PATHS: &[&str] = &[
+    "/usr/share/hwdata/pci.ids",
+    "/usr/share/misc/pci.ids",
+    "/usr/share/pci.ids",
+    "/usr/share/pciids/pci.ids",
+];
+
+/// Loads the system's `pci.ids`, empty if there is none, in which case GPUs
+/// are named by their IDs.
+pub fn load(ctx: &Context) -> String {
+    PCI_IDS_PATHS
+        .iter()
+        .find_map(|path| fs::read_to_string(ctx.path(path)).ok())
+        .unwrap_or_default()
+}
+
+/// Looks up the vendor name and, if listed, the device name.
+pub fn lookup(db: &str, vendor: u16, device: u16) -> Option<(String, Option<String>)> {
+    let vendor_id = format!("{:04x}", vendor);
+    let device_id = format!("{:04x}", device);
+
+    let mut lines = db.lines();
+    let vendor_name = lines.find_map(|line| {
+        line.strip_prefix(vendor_id.as_str())
+            .filter(|rest| rest.starts_with("  "))
+            .map(|rest| rest.trim().to_string())
+    })?;
+
+    // Devices are the tab-indented lines until the next vendor.
+    let device_name = lines
+        .take_while(|line| line.starts_with('\t') || line.starts_with('#') || line.is_empty())
+        .filter(|line| !line.starts_with("\t\t"))
+        .find_map(|line| {
+            line.strip_prefix('\t')?
+                .strip_prefix(device_id.as_str())
+                .filter(|rest| rest.starts_with("  "))
+                .map(|rest| rest.trim().to_string())
+        });
+    Some((vendor_name, device_name))
+}
@@ -0,0 +1,121 @@
+use super::{command, dmi, Context, Module, ModuleError, Value};
+use std::process::Command;
+
+/// Machine model, e.g. "LENOVO 20XW ThinkPad X1 Carbon Gen 9".
+pub struct Host;
+
+impl Module for Host {
+    fn name(&self) -> &'static str {
+        "host"
+    }
+
+    fn label(&self) -> &'static str {
+        "Host"
+    }
+
+    fn description(&self) -> &'static str {
+        "Machine vendor and model"
+    }
+
+    fn collect(&self, ctx: &Context) -> Result<Value, ModuleError> {
+        let product = join([
+            dmi::read(ctx, "sys_vendor"),
+            dmi::read(ctx, "product_name"),
+            dmi::read(ctx, "product_version"),
+        ]);
+        // Self-built desktops often only fill in the board fields.
+        product
+            .or_else(|| join([dmi::read(ctx, "board_vendor"), dmi::read(ctx, "board_name")]))
+            .or_else(|| dmi::devicetree_model(ctx))
+            .or_else(|| {
+                command::stdout(
+                    Command::new("sysctl").args(["-n", "hw.model"]),
+                    ctx.timeout(self.name()),
+                )
+                .map(|model| model.trim().to_string())
+                .filter(|model| !model.is_empty())
+            })
+            .map(Value::Text)
+            .ok_or(ModuleError::Unavailable)
+    }
+}
+
+/// Mainboard vendor and model. Hidden unless `show_board` is set.
+pub struct Board;
+
+impl Module for Board {
+    fn name(&self) -> &'static str {
+        "board"
+    }
+
+    fn label(&self) -> &'static str {
+        "Board"
+    }
+
+    fn description(&self) -> &'static str {
+        "Mainboard vendor, model and revision"
+    }
+
+    fn shown_by_default(&self) -> bool {
+        false
+    }
+
+    fn collect(&self, ctx: &Context) -> Result<Value, ModuleError> {
+        join([
+            dmi::read(ctx, "board_vendor"),
+            dmi::read(ctx, "board_name"),
+            dmi::read(ctx, "board_version"),
+        ])
+        .map(Value::Text)
+        .ok_or(ModuleError::Unavailable)
+    }
+}
+
+/// Firmware vendor, version and release date. Hidden unless `show_bios` is set.
+pub struct Bios;
+
+impl Module for Bios {
+    fn name(&self) -> &'static str {
+        "bios"
+    }
+
+    fn label(&self) -> &'static str {
+        "BIOS"
+    }
+
+    fn description(&self) -> &'static str {
+        "Firmware vendor, version and release date"
+    }
+
+    fn shown_by_default(&self) -> bool {
+        false
+    }
+
+    fn collect(&self, ctx: &Context) -> Result<Value, ModuleError> {
+        let firmware = join([
+            dmi::read(ctx, "bios_vendor"),
+            dmi::read(ctx, "bios_version"),
+        ])
+        .ok_or(ModuleError::Unavailable)?;
+        Ok(match dmi::read(ctx, "bios_date") {
+            Some(date) => format!("{} ({})", firmware, date),
+            None => firmware,
+        }
+        .into())
+    }
+}
+
+/// Joins the present parts, dropping any that repeat the previous one, as
+/// vendors often put their name in the product field too.
+fn join<const N: usize>(parts: [Option<String>; N]) -> Option<String> {
+    let mut joined = String::new();
+    for part in parts.into_iter().flatten() {
+        if joined.is_empty() || part.starts_with(&joined) {
+            joined = part;
+        } else if !joined.ends_with(&part) {
+            joined.push(' ');
+            joined.push_str(&part);
+        }
+    }
+    Some(joined).filter(|joined| !joined.is_empty())
+}
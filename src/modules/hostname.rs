@@ -0,0 +1,24 @@
+use super::{Context, Module, ModuleError, Value};
+use sysinfo::System;
+
+pub struct Hostname;
+
+impl Module for Hostname {
+    fn name(&self) -> &'static str {
+        "hostname"
+    }
+
+    fn label(&self) -> &'static str {
+        "Hostname"
+    }
+
+    fn description(&self) -> &'static str {
+        "Network name of the machine"
+    }
+
+    fn collect(&self, _ctx: &Context) -> Result<Value, ModuleError> {
+        System::host_name()
+            .map(Value::Text)
+            .ok_or(ModuleError::Unavailable)
+    }
+}
@@ -0,0 +1,24 @@
+use super::{Context, Module, ModuleError, Value};
+use sysinfo::System;
+
+pub struct Kernel;
+
+impl Module for Kernel {
+    fn name(&self) -> &'static str {
+        "kernel"
+    }
+
+    fn label(&self) -> &'static str {
+        "Kernel"
+    }
+
+    fn description(&self) -> &'static str {
+        "Kernel version"
+    }
+
+    fn collect(&self, _ctx: &Context) -> Result<Value, ModuleError> {
+        System::kernel_version()
+            .map(Value::Text)
+            .ok_or(ModuleError::Unavailable)
+    }
+}
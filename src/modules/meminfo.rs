@@ -0,0 +1,86 @@
+//! `/proc/meminfo`, `/proc/swaps`, zram and ZFS ARC statistics.
+
+use super::Context;
+use std::collections::HashMap;
+use std::fs;
+
+/// `/proc/meminfo` values in bytes, keyed by field name (`MemTotal`, ...).
+pub fn read_meminfo(ctx: &Context) -> Option<HashMap<String, u64>> {
+    let contents = fs::read_to_string(ctx.path("/proc/meminfo")).ok()?;
+    let mut fields = HashMap::new();
+    for line in contents.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let mut parts = value.split_whitespace();
+        let Some(Ok(amount)) = parts.next().map(str::parse::<u64>) else {
+            continue;
+        };
+        let bytes = match parts.next() {
+            Some("kB") => amount * 1024,
+            _ => amount,
+        };
+        fields.insert(key.to_string(), bytes);
+    }
+    Some(fields)
+}
+
+/// Current size of the ZFS ARC in bytes. The kernel counts it as used
+/// memory even though ZFS gives it back under pressure.
+pub fn zfs_arc_size(ctx: &Context) -> Option<u64> {
+    let stats = fs::read_to_string(ctx.path("/proc/spl/kstat/zfs/arcstats")).ok()?;
+    stats.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        if parts.next()? != "size" {
+            return None;
+        }
+        parts.nth(1)?.parse().ok()
+    })
+}
+
+pub struct SwapDevice {
+    pub name: String,
+    pub kind: String,
+    pub size: u64,
+    pub used: u64,
+    /// Uncompressed over compressed size, for zram devices.
+    pub compression: Option<f64>,
+}
+
+/// Active swap areas from `/proc/swaps`.
+pub fn swap_devices(ctx: &Context) -> Vec<SwapDevice> {
+    let Ok(contents) = fs::read_to_string(ctx.path("/proc/swaps")) else {
+        return Vec::new();
+    };
+    contents
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let [name, kind, size, used, ..] = parts[..] else {
+                return None;
+            };
+            let device = name.rsplit('/').next().unwrap_or(name);
+            let is_zram = device.starts_with("zram");
+            Some(SwapDevice {
+                name: name.to_string(),
+                kind: if is_zram { "zram" } else { kind }.to_string(),
+                size: size.parse::<u64>().ok()? * 1024,
+                used: used.parse::<u64>().ok()? * 1024,
+                compression: is_zram.then(|| zram_ratio(ctx, device)).flatten(),
+            })
+        })
+        .collect()
+}
+
+/// `orig_data_size / compr_data_size` from `/sys/block/zram*/mm_stat`.
+fn zram_ratio(ctx: &Context, device: &str) -> Option<f64> {
+    let stat = fs::read_to_string(ctx.path(&format!("/sys/block/{}/mm_stat", device))).ok()?;
+    let mut fields = stat.split_whitespace().map(str::parse::<u64>);
+    let original = fields.next()?.ok()?;
+    let compressed = fields.next()?.ok()?;
+    if compressed == 0 {
+        return None;
+    }
+    Some(original as f64 / compressed as f64)
+}
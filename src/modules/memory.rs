@@ -0,0 +1,74 @@
+use super::{meminfo, Context, Module, ModuleError, Refresh, Value};
+use crate::units;
+
+pub struct Memory;
+
+impl Module for Memory {
+    fn name(&self) -> &'static str {
+        "memory"
+    }
+
+    fn label(&self) -> &'static str {
+        "Memory"
+    }
+
+    fn description(&self) -> &'static str {
+        "Used and total memory, optionally broken down from /proc/meminfo"
+    }
+
+    fn refreshes(&self) -> &'static [Refresh] {
+        &[Refresh::Memory]
+    }
+
+    fn collect(&self, ctx: &Context) -> Result<Value, ModuleError> {
+        let options = &ctx.config.memory;
+        if options.detailed {
+            if let Some(lines) = detailed(ctx) {
+                return Ok(Value::Lines(lines));
+            }
+        }
+
+        let total_memory = ctx.sys.total_memory();
+        if total_memory == 0 {
+            return Err(ModuleError::Unavailable);
+        }
+        let mut used_memory = total_memory.saturating_sub(ctx.sys.available_memory());
+        if options.subtract_zfs_arc {
+            used_memory = used_memory.saturating_sub(meminfo::zfs_arc_size(ctx).unwrap_or(0));
+        }
+        Ok(units::format_usage(used_memory, total_memory, &ctx.config.units).into())
+    }
+}
+
+/// `used / total` followed by the cache, buffer, shared and available split.
+fn detailed(ctx: &Context) -> Option<Vec<String>> {
+    let fields = meminfo::read_meminfo(ctx)?;
+    let field = |key: &str| fields.get(key).copied().unwrap_or(0);
+    let total = *fields.get("MemTotal")?;
+    let mut available = field("MemAvailable");
+
+    let arc = if ctx.config.memory.subtract_zfs_arc {
+        meminfo::zfs_arc_size(ctx)
+    } else {
+        None
+    };
+    if let Some(arc) = arc {
+        available = (available + arc).min(total);
+    }
+    let used = total.saturating_sub(available);
+
+    let size = |bytes| units::format_size(bytes, &ctx.config.units);
+    let mut breakdown = vec![
+        format!("cached {}", size(field("Cached"))),
+        format!("buffers {}", size(field("Buffers"))),
+        format!("shared {}", size(field("Shmem"))),
+        format!("available {}", size(available)),
+    ];
+    if let Some(arc) = arc {
+        breakdown.push(format!("ZFS ARC {}", size(arc)));
+    }
+    Some(vec![
+        units::format_usage(used, total, &ctx.config.units),
+        breakdown.join(", "),
+    ])
+}
@@ -0,0 +1,371 @@
+//! Information modules shown next to the ASCII art.
+//!
+//! Every field nekofetch can print is a [`Module`]. The renderer walks
+//! [`registry`] in order, so adding a field means writing a module and
+//! listing it below; the config picks up its `show_<name>` toggle for free.
+
+mod battery;
+pub mod command;
+mod cpu;
+mod de;
+mod dmi;
+#[cfg(test)]
+mod fixture;
+mod gpu;
+mod host;
+mod hostname;
+mod kernel;
+mod meminfo;
+mod memory;
+mod network;
+mod os;
+pub mod os_release;
+mod packages;
+mod resolution;
+mod shell;
+mod storage;
+mod swap;
+mod terminal;
+mod theme;
+mod uptime;
+mod username;
+mod virtualization;
+mod wm;
+
+use crate::config::Config;
+use os_release::OsRelease;
+pub use packages::package_managers;
+use std::collections::HashSet;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+use sysinfo::{
+    CpuRefreshKind, MemoryRefreshKind, Pid, Process, ProcessRefreshKind, RefreshKind, System,
+    UpdateKind,
+};
+
+/// A collected value, ready to be rendered.
+pub enum Value {
+    /// A single line of text.
+    Text(String),
+    /// Several lines rendered under the same label.
+    Lines(Vec<String>),
+}
+
+impl From<String> for Value {
+    fn from(text: String) -> Self {
+        Value::Text(text)
+    }
+}
+
+/// Why a module could not produce a value.
+#[derive(Debug)]
+pub enum ModuleError {
+    /// The information is not available on this system.
+    Unavailable,
+    /// The module did not finish before its deadline.
+    TimedOut,
+    /// The hardware the module reports on does not exist here (e.g. a battery
+    /// on a desktop), so the line is left out.
+    NotPresent,
+}
+
+impl fmt::Display for ModuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModuleError::Unavailable => write!(f, "Unknown"),
+            ModuleError::TimedOut => write!(f, "(timed out)"),
+            ModuleError::NotPresent => write!(f, "None"),
+        }
+    }
+}
+
+/// Parts of the shared `System` a module reads.
+///
+/// Only what the enabled modules ask for gets refreshed, so a module must list
+/// everything it touches on `Context::sys` here.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Refresh {
+    Cpu,
+    Memory,
+    /// Process names, parents and executables, without CPU or memory usage.
+    Processes,
+}
+
+/// Shared state handed to every module while collecting.
+pub struct Context {
+    pub sys: System,
+    pub config: Config,
+    os_release: OnceLock<Option<OsRelease>>,
+    theme_settings: OnceLock<theme::Settings>,
+}
+
+impl Context {
+    pub fn new(sys: System, config: Config) -> Self {
+        Context {
+            sys,
+            config,
+            os_release: OnceLock::new(),
+            theme_settings: OnceLock::new(),
+        }
+    }
+
+    /// Parsed `os-release`, read on first use and shared between modules.
+    pub fn os_release(&self) -> Option<&OsRelease> {
+        self.os_release
+            .get_or_init(|| OsRelease::load(self))
+            .as_ref()
+    }
+
+    /// Toolkit settings read by the theme modules, loaded on first use.
+    fn theme_settings(&self) -> &theme::Settings {
+        self.theme_settings.get_or_init(|| theme::Settings::load(self))
+    }
+
+    /// Resolves an absolute system path against the configured root. An
+    /// empty root, as left by uncommenting the generated config, is `/`.
+    pub fn path(&self, path: &str) -> PathBuf {
+        let root = self.config.root.as_deref();
+        match root.filter(|root| !root.as_os_str().is_empty()) {
+            Some(root) => root.join(path.trim_start_matches('/')),
+            None => Path::new(path).to_path_buf(),
+        }
+    }
+
+    /// Resolves `rel` inside the user's home directory, under the root.
+    pub fn home_path(&self, rel: &str) -> Option<PathBuf> {
+        let home = dirs::home_dir()?;
+        Some(self.path(&home.to_string_lossy()).join(rel))
+    }
+
+    /// Resolves `rel` inside `$XDG_CONFIG_HOME`, under the root.
+    pub fn config_path(&self, rel: &str) -> Option<PathBuf> {
+        match std::env::var_os("XDG_CONFIG_HOME") {
+            Some(dir) if !dir.is_empty() => Some(self.path(&dir.to_string_lossy()).join(rel)),
+            _ => self.home_path(&format!(".config/{}", rel)),
+        }
+    }
+
+    /// nekofetch's parent processes, closest first. Needs `Refresh::Processes`.
+    pub fn ancestors(&self) -> Vec<&Process> {
+        let Ok(pid) = sysinfo::get_current_pid() else {
+            return Vec::new();
+        };
+        parent_pids(pid, |pid| self.sys.process(pid)?.parent())
+            .into_iter()
+            .filter_map(|pid| self.sys.process(pid))
+            .collect()
+    }
+
+    /// How long the module called `name` may take.
+    pub fn timeout(&self, name: &str) -> Duration {
+        Duration::from_millis(self.config.timeouts.for_module(name))
+    }
+}
+
+/// The pids above `pid`, closest first. Stops at pid 0 and at the first pid
+/// seen twice: macOS lists kernel_task as its own parent, and reused pids on
+/// Windows can form a cycle.
+fn parent_pids(pid: Pid, parent: impl Fn(Pid) -> Option<Pid>) -> Vec<Pid> {
+    let mut visited = HashSet::from([pid]);
+    let mut parents = Vec::new();
+    let mut pid = pid;
+    while let Some(next) = parent(pid) {
+        if next == Pid::from(0) || !visited.insert(next) {
+            break;
+        }
+        parents.push(next);
+        pid = next;
+    }
+    parents
+}
+
+pub trait Module: Sync {
+    /// Config key of the module, also used in `show_<name>`.
+    fn name(&self) -> &'static str;
+    /// Label printed in front of the value.
+    fn label(&self) -> &'static str;
+    /// One-line description of what the module shows.
+    fn description(&self) -> &'static str;
+    /// sysinfo data this module needs refreshed before `collect`.
+    fn refreshes(&self) -> &'static [Refresh] {
+        &[]
+    }
+    /// Whether the module is shown when the config has no `show_<name>` key.
+    fn shown_by_default(&self) -> bool {
+        true
+    }
+    /// Time `collect` spends waiting on purpose, such as a usage sample.
+    /// It is added to the module's deadline.
+    fn waits(&self, _config: &Config) -> Duration {
+        Duration::ZERO
+    }
+    fn collect(&self, ctx: &Context) -> Result<Value, ModuleError>;
+}
+
+/// All modules, in display order.
+static REGISTRY: &[&dyn Module] = &[
+    &username::Username,
+    &hostname::Hostname,
+    &os::Os,
+    &host::Host,
+    &host::Board,
+    &host::Bios,
+    &kernel::Kernel,
+    &uptime::Uptime,
+    &packages::Packages,
+    &shell::Shell,
+    &resolution::Resolution,
+    &de::De,
+    &wm::Wm,
+    &theme::Theme,
+    &theme::QtStyle,
+    &theme::Icons,
+    &theme::Cursor,
+    &theme::Font,
+    &terminal::Terminal,
+    &cpu::Cpu,
+    &gpu::Gpu,
+    &memory::Memory,
+    &swap::Swap,
+    &storage::Storage,
+    &network::Network,
+    &battery::Battery,
+    &virtualization::Virtualization,
+];
+
+pub fn registry() -> &'static [&'static dyn Module] {
+    REGISTRY
+}
+
+pub fn find(name: &str) -> Option<&'static dyn Module> {
+    REGISTRY
+        .iter()
+        .copied()
+        .find(|module| module.name() == name)
+}
+
+/// Builds the smallest `RefreshKind` that covers `modules`.
+pub fn refresh_kind(modules: &[&dyn Module]) -> RefreshKind {
+    let needs = |refresh| {
+        modules
+            .iter()
+            .any(|module| module.refreshes().contains(&refresh))
+    };
+
+    let mut kind = RefreshKind::new();
+    if needs(Refresh::Cpu) {
+        kind = kind.with_cpu(CpuRefreshKind::new().with_frequency());
+    }
+    if needs(Refresh::Memory) {
+        kind = kind.with_memory(MemoryRefreshKind::new().with_ram().with_swap());
+    }
+    if needs(Refresh::Processes) {
+        kind = kind.with_processes(ProcessRefreshKind::new().with_exe(UpdateKind::OnlyIfNotSet));
+    }
+    kind
+}
+
+/// Collects `modules` concurrently, returning results in the same order.
+///
+/// Each module gets until its own deadline, plus the time it
+/// [`waits`](Module::waits); one that misses it is reported as
+/// `ModuleError::TimedOut` and its thread is left to finish on its own.
+pub fn collect_all(
+    ctx: &Arc<Context>,
+    modules: &[&'static dyn Module],
+) -> Vec<Result<Value, ModuleError>> {
+    let (tx, rx) = mpsc::channel();
+    let start = Instant::now();
+    let mut deadlines = Vec::with_capacity(modules.len());
+    for (index, &module) in modules.iter().enumerate() {
+        deadlines.push(start + ctx.timeout(module.name()) + module.waits(&ctx.config));
+        let tx = tx.clone();
+        let ctx = Arc::clone(ctx);
+        thread::spawn(move || {
+            // The receiver is gone once every module is done or timed out.
+            let _ = tx.send((index, module.collect(&ctx)));
+        });
+    }
+    drop(tx);
+
+    let mut results: Vec<Option<Result<Value, ModuleError>>> =
+        modules.iter().map(|_| None).collect();
+    loop {
+        let now = Instant::now();
+        for (result, deadline) in results.iter_mut().zip(&deadlines) {
+            if result.is_none() && *deadline <= now {
+                *result = Some(Err(ModuleError::TimedOut));
+            }
+        }
+
+        let next_deadline = results
+            .iter()
+            .zip(&deadlines)
+            .filter(|(result, _)| result.is_none())
+            .map(|(_, deadline)| *deadline)
+            .min();
+        let Some(next_deadline) = next_deadline else {
+            break;
+        };
+
+        match rx.recv_timeout(next_deadline.saturating_duration_since(now)) {
+            Ok((index, result)) => {
+                results[index].get_or_insert(result);
+            }
+            Err(mpsc::RecvTimeoutError::Timeout) => {}
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
+        }
+    }
+
+    // Threads of modules that timed out are abandoned; their children go now.
+    command::kill_running();
+
+    results
+        .into_iter()
+        .map(|result| result.unwrap_or(Err(ModuleError::Unavailable)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn walk(start: usize, parents: &[(usize, usize)]) -> Vec<usize> {
+        let parents: HashMap<Pid, Pid> = parents
+            .iter()
+            .map(|&(child, parent)| (Pid::from(child), Pid::from(parent)))
+            .collect();
+        parent_pids(Pid::from(start), |pid| parents.get(&pid).copied())
+            .into_iter()
+            .map(|pid| pid.as_u32() as usize)
+            .collect()
+    }
+
+    #[test]
+    fn labels_are_unique() {
+        let mut labels = HashSet::new();
+        for module in registry() {
+            assert!(labels.insert(module.label()), "{}", module.label());
+        }
+    }
+
+    #[test]
+    fn parents_end_at_root() {
+        assert_eq!(walk(300, &[(300, 200), (200, 1)]), [200, 1]);
+    }
+
+    #[test]
+    fn parents_stop_at_pid_0() {
+        assert_eq!(walk(300, &[(300, 1), (1, 0), (0, 0)]), [1]);
+    }
+
+    #[test]
+    fn parents_stop_at_cycle() {
+        assert_eq!(walk(300, &[(300, 200), (200, 100), (100, 200)]), [200, 100]);
+        assert_eq!(walk(300, &[(300, 300)]), Vec::<usize>::new());
+    }
+}
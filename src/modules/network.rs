@@ -0,0 +1,122 @@
+use super::{Context, Module, ModuleError, Value};
+use std::fs;
+use std::net::IpAddr;
+use std::path::Path;
+use sysinfo::Networks;
+
+/// Interface name prefixes of container and VM bridges and their veth pairs.
+const BRIDGE_PREFIXES: &[&str] = &[
+    "docker", "veth", "br-", "virbr", "vnet", "lxcbr", "lxdbr", "podman", "cni", "flannel", "cali",
+];
+
+pub struct Network;
+
+impl Module for Network {
+    fn name(&self) -> &'static str {
+        "network"
+    }
+
+    fn label(&self) -> &'static str {
+        "Network"
+    }
+
+    fn description(&self) -> &'static str {
+        "Active network interfaces with their addresses, type and link speed"
+    }
+
+    fn collect(&self, ctx: &Context) -> Result<Value, ModuleError> {
+        let options = &ctx.config.network;
+        let networks = Networks::new_with_refreshed_list();
+        let mut interfaces: Vec<_> = networks.list().iter().collect();
+        interfaces.sort_by(|a, b| a.0.cmp(b.0));
+
+        let mut lines = Vec::new();
+        for (name, data) in interfaces {
+            let sys_dir = ctx.path("/sys/class/net").join(name);
+            let kind = interface_kind(&sys_dir, name);
+            if options.hide_loopback && kind == "loopback" {
+                continue;
+            }
+            if options.hide_bridges && BRIDGE_PREFIXES.iter().any(|p| name.starts_with(p)) {
+                continue;
+            }
+            let state = read(&sys_dir, "operstate");
+            if matches!(
+                state.as_deref(),
+                Some("down" | "lowerlayerdown" | "notpresent")
+            ) {
+                continue;
+            }
+
+            // IPv4 sorts before IPv6.
+            let mut networks: Vec<_> = data
+                .ip_networks()
+                .iter()
+                .filter(|net| !(options.hide_link_local && is_link_local(&net.addr)))
+                .collect();
+            networks.sort();
+            let addresses: Vec<String> = networks.iter().map(|net| net.to_string()).collect();
+            if addresses.is_empty() {
+                continue;
+            }
+
+            let mut details = vec![kind.to_string()];
+            if let Some(speed) = link_speed(&sys_dir) {
+                details.push(speed);
+            }
+            let mut line = format!(
+                "{} ({}): {}",
+                name,
+                details.join(", "),
+                addresses.join(", ")
+            );
+            let mac = data.mac_address();
+            if options.show_mac && !mac.is_unspecified() {
+                line.push_str(&format!(" [{}]", mac));
+            }
+            lines.push(line);
+        }
+
+        if lines.is_empty() {
+            return Err(ModuleError::Unavailable);
+        }
+        Ok(Value::Lines(lines))
+    }
+}
+
+/// Physical interfaces have a `device` link; wireless ones also expose
+/// `wireless` or `phy80211`.
+fn interface_kind(sys_dir: &Path, name: &str) -> &'static str {
+    if name == "lo" || read(sys_dir, "type").as_deref() == Some("772") {
+        "loopback"
+    } else if sys_dir.join("wireless").exists() || sys_dir.join("phy80211").exists() {
+        "wireless"
+    } else if sys_dir.join("device").exists() {
+        "wired"
+    } else {
+        "virtual"
+    }
+}
+
+/// Negotiated speed; drivers report -1 or fail the read while it is unknown.
+fn link_speed(sys_dir: &Path) -> Option<String> {
+    let mbps: i64 = read(sys_dir, "speed")?.parse().ok()?;
+    match mbps {
+        ..=0 => None,
+        mbps if mbps >= 1000 && mbps % 1000 == 0 => Some(format!("{} Gb/s", mbps / 1000)),
+        mbps => Some(format!("{} Mb/s", mbps)),
+    }
+}
+
+fn is_link_local(addr: &IpAddr) -> bool {
+    match addr {
+        IpAddr::V4(addr) => addr.is_link_local(),
+        IpAddr::V6(addr) => addr.segments()[0] & 0xffc0 == 0xfe80,
+    }
+}
+
+fn read(dir: &Path, file: &str) -> Option<String> {
+    fs::read_to_string(dir.join(file))
+        .ok()
+        .map(|contents| contents.trim().to_string())
+}
@@ -0,0 +1,41 @@
+use super::{Context, Module, ModuleError, Value};
+use sysinfo::System;
+
+pub struct Os;
+
+impl Module for Os {
+    fn name(&self) -> &'static str {
+        "os"
+    }
+
+    fn label(&self) -> &'static str {
+        "OS"
+    }
+
+    fn description(&self) -> &'static str {
+        "Operating system name and version"
+    }
+
+    fn collect(&self, ctx: &Context) -> Result<Value, ModuleError> {
+        if let Some(release) = ctx.os_release() {
+            let parts: Vec<String> = ctx
+                .config
+                .os
+                .fields
+                .iter()
+                .filter_map(|field| release.field(field))
+                .filter(|value| !value.is_empty())
+                .collect();
+            if !parts.is_empty() {
+                return Ok(parts.join(" ").into());
+            }
+        }
+
+        // No os-release (macOS, Windows): the long version already starts
+        // with the OS name.
+        System::long_os_version()
+            .or_else(System::name)
+            .map(Value::Text)
+            .ok_or(ModuleError::Unavailable)
+    }
+}
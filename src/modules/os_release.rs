@@ -0,0 +1,92 @@
+//! Distro identity from `os-release(5)`.
+
+use super::Context;
+use std::fs;
+
+/// The `os-release` fields nekofetch cares about. Missing keys are `None`,
+/// except `ID`, which defaults to `linux` like the spec says.
+#[derive(Default)]
+pub struct OsRelease {
+    pub name: Option<String>,
+    pub pretty_name: Option<String>,
+    pub id: String,
+    pub id_like: Vec<String>,
+    pub version_id: Option<String>,
+    pub variant: Option<String>,
+    pub build_id: Option<String>,
+}
+
+impl OsRelease {
+    /// Reads `/etc/os-release`, falling back to `/usr/lib/os-release`.
+    pub fn load(ctx: &Context) -> Option<Self> {
+        let contents = fs::read_to_string(ctx.path("/etc/os-release"))
+            .or_else(|_| fs::read_to_string(ctx.path("/usr/lib/os-release")))
+            .ok()?;
+        Some(Self::parse(&contents))
+    }
+
+    pub fn parse(contents: &str) -> Self {
+        let mut release = OsRelease {
+            id: "linux".to_string(),
+            ..Default::default()
+        };
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.starts_with('#') {
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else {
+                continue;
+            };
+            let value = unquote(value.trim());
+            match key.trim() {
+                "NAME" => release.name = Some(value),
+                "PRETTY_NAME" => release.pretty_name = Some(value),
+                "ID" => release.id = value,
+                "ID_LIKE" => {
+                    release.id_like = value.split_whitespace().map(str::to_string).collect()
+                }
+                "VERSION_ID" => release.version_id = Some(value),
+                "VARIANT" => release.variant = Some(value),
+                "BUILD_ID" => release.build_id = Some(value),
+                _ => {}
+            }
+        }
+        release
+    }
+
+    /// Looks a field up by its lowercase key, as used in the config.
+    pub fn field(&self, key: &str) -> Option<String> {
+        match key {
+            "name" => self.name.clone(),
+            "pretty_name" => self.pretty_name.clone(),
+            "id" => Some(self.id.clone()),
+            "id_like" if !self.id_like.is_empty() => Some(self.id_like.join(" ")),
+            "version_id" => self.version_id.clone(),
+            "variant" => self.variant.clone(),
+            "build_id" => self.build_id.clone(),
+            _ => None,
+        }
+    }
+}
+
+/// Strips shell-style quoting, undoing the escapes the spec allows.
+fn unquote(value: &str) -> String {
+    let quoted = value.len() >= 2
+        && (value.starts_with('"') && value.ends_with('"')
+            || value.starts_with('\'') && value.ends_with('\''));
+    if !quoted {
+        return value.to_string();
+    }
+
+    let inner = &value[1..value.len() - 1];
+    let mut out = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => out.extend(chars.next()),
+            c => out.push(c),
+        }
+    }
+    out
+}
@@ -0,0 +1,201 @@
+use super::{Context, Module, ModuleError, Value};
+use std::fs;
+use std::path::Path;
+
+mod sqlite;
+mod universal;
+
+/// Reads one package manager's database, `None` if it is not installed.
+type Counter = fn(&Context) -> Option<usize>;
+
+/// Package managers read straight from their on-disk state, in display order.
+/// Each can be turned off under `packages:` in the config.
+const MANAGERS: &[(&str, Counter)] = &[
+    ("dpkg", count_dpkg),
+    ("pacman", count_pacman),
+    ("rpm", count_rpm),
+    ("apk", count_apk),
+    ("xbps", count_xbps),
+    ("brew", universal::count_brew),
+    ("flatpak", universal::count_flatpak),
+    ("snap", universal::count_snap),
+    ("nix", universal::count_nix),
+    ("cargo", universal::count_cargo),
+    ("pipx", universal::count_pipx),
+    ("npm", universal::count_npm),
+];
+
+/// Names accepted under `packages:` in the config.
+pub fn package_managers() -> impl Iterator<Item = &'static str> {
+    MANAGERS.iter().map(|(name, _)| *name)
+}
+
+pub struct Packages;
+
+impl Module for Packages {
+    fn name(&self) -> &'static str {
+        "packages"
+    }
+
+    fn label(&self) -> &'static str {
+        "Packages"
+    }
+
+    fn description(&self) -> &'static str {
+        "Number of installed packages per package manager"
+    }
+
+    fn collect(&self, ctx: &Context) -> Result<Value, ModuleError> {
+        let counts: Vec<String> = MANAGERS
+            .iter()
+            .filter(|(name, _)| ctx.config.packages.enabled(name))
+            .filter_map(|(name, count)| match count(ctx) {
+                Some(count) if count > 0 => Some(format!("{} ({})", count, name)),
+                _ => None,
+            })
+            .collect();
+        if counts.is_empty() {
+            return Err(ModuleError::Unavailable);
+        }
+        Ok(counts.join(", ").into())
+    }
+}
+
+/// Counts stanzas in the dpkg status file whose state is `installed`, which
+/// skips removed packages that only left their config files behind.
+fn count_dpkg(ctx: &Context) -> Option<usize> {
+    let status = fs::read_to_string(ctx.path("/var/lib/dpkg/status")).ok()?;
+    let count = status
+        .lines()
+        .filter_map(|line| line.strip_prefix("Status:"))
+        .filter(|status| status.split_whitespace().last() == Some("installed"))
+        .count();
+    Some(count)
+}
+
+/// pacman keeps one directory per installed package.
+fn count_pacman(ctx: &Context) -> Option<usize> {
+    count_dirs(&ctx.path("/var/lib/pacman/local"))
+}
+
+/// Counts rows of the `Packages` table in the sqlite rpmdb used since
+/// rpm 4.16. Older Berkeley DB and NDB databases are not read.
+fn count_rpm(ctx: &Context) -> Option<usize> {
+    let db = fs::read(ctx.path("/var/lib/rpm/rpmdb.sqlite"))
+        .or_else(|_| fs::read(ctx.path("/usr/lib/sysimage/rpm/rpmdb.sqlite")))
+        .ok()?;
+    sqlite::count_rows(&db, "Packages")
+}
+
+/// Every package in apk's database starts with a `P:` (name) line.
+fn count_apk(ctx: &Context) -> Option<usize> {
+    let installed = fs::read_to_string(ctx.path("/lib/apk/db/installed")).ok()?;
+    Some(
+        installed
+            .lines()
+            .filter(|line| line.starts_with("P:"))
+            .count(),
+    )
+}
+
+/// xbps records each package's state in a plist; count the installed ones.
+fn count_xbps(ctx: &Context) -> Option<usize> {
+    let entries = fs::read_dir(ctx.path("/var/db/xbps")).ok()?;
+    let pkgdb = entries.flatten().find(|entry| {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        name.starts_with("pkgdb-") && name.ends_with(".plist")
+    })?;
+    let plist = fs::read_to_string(pkgdb.path()).ok()?;
+    Some(plist.matches("<string>installed</string>").count())
+}
+
+pub(super) fn count_dirs(path: &Path) -> Option<usize> {
+    let entries = fs::read_dir(path).ok()?;
+    Some(
+        entries
+            .flatten()
+            .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+            .count(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::fixture::Fixture;
+
+    #[test]
+    fn dpkg_skips_config_files_only() {
+        let fixture = Fixture::new();
+        fixture.file(
+            "/var/lib/dpkg/status",
+            "Package: bash\nStatus: install ok installed\nVersion: 5.2\n\n\
+             Package: old\nStatus: deinstall ok config-files\nVersion: 1.0\n\n\
+             Package: coreutils\nStatus: install ok installed\n",
+        );
+        assert_eq!(count_dpkg(&fixture.context()), Some(2));
+    }
+
+    #[test]
+    fn pacman_ignores_db_version_file() {
+        let fixture = Fixture::new();
+        fixture
+            .dir("/var/lib/pacman/local/bash-5.2-1")
+            .dir("/var/lib/pacman/local/glibc-2.39-1")
+            .file("/var/lib/pacman/local/ALPM_DB_VERSION", "9\n");
+        assert_eq!(count_pacman(&fixture.context()), Some(2));
+    }
+
+    #[test]
+    fn rpm_single_page() {
+        let fixture = Fixture::new();
+        fixture.file(
+            "/var/lib/rpm/rpmdb.sqlite",
+            include_bytes!("fixtures/rpmdb-single-page.sqlite"),
+        );
+        assert_eq!(count_rpm(&fixture.context()), Some(3));
+    }
+
+    #[test]
+    fn rpm_interior_page_in_sysimage() {
+        let fixture = Fixture::new();
+        fixture.file(
+            "/usr/lib/sysimage/rpm/rpmdb.sqlite",
+            include_bytes!("fixtures/rpmdb-interior.sqlite"),
+        );
+        assert_eq!(count_rpm(&fixture.context()), Some(300));
+    }
+
+    #[test]
+    fn apk_counts_name_lines() {
+        let fixture = Fixture::new();
+        fixture.file(
+            "/lib/apk/db/installed",
+            "C:Q1abc=\nP:musl\nV:1.2.4\nF:lib\n\nC:Q1def=\nP:busybox\nV:1.36\n",
+        );
+        assert_eq!(count_apk(&fixture.context()), Some(2));
+    }
+
+    #[test]
+    fn xbps_counts_installed_state() {
+        let fixture = Fixture::new();
+        fixture.file(
+            "/var/db/xbps/pkgdb-0.38.plist",
+            "<plist><dict>\
+             <key>bash</key><dict><key>state</key><string>installed</string></dict>\
+             <key>vim</key><dict><key>state</key><string>half-removed</string></dict>\
+             <key>zsh</key><dict><key>state</key><string>installed</string></dict>\
+             </dict></plist>",
+        );
+        assert_eq!(count_xbps(&fixture.context()), Some(2));
+    }
+
+    #[test]
+    fn missing_databases() {
+        let ctx = Fixture::new().context();
+        for count in [count_dpkg, count_pacman, count_rpm, count_apk, count_xbps] {
+            assert_eq!(count(&ctx), None);
+        }
+    }
+}
@@ -0,0 +1,202 @@
+//! Just enough of the SQLite file format to count the rows of a table.
+//!
+//! See <https://www.sqlite.org/fileformat.html>. Only the main database file
+//! is read, so rows still sitting in a `-wal` file are not counted.
+
+const HEADER_SIZE: usize = 100;
+const INTERIOR_TABLE: u8 = 0x05;
+const LEAF_TABLE: u8 = 0x0d;
+
+/// Counts the rows of `table` in the database image `db`.
+pub fn count_rows(db: &[u8], table: &str) -> Option<usize> {
+    if !db.starts_with(b"SQLite format 3\0") || db.len() < HEADER_SIZE {
+        return None;
+    }
+    let page_size = match u16::from_be_bytes([db[16], db[17]]) {
+        1 => 65536,
+        size => size as usize,
+    };
+    let file = File { db, page_size };
+
+    // sqlite_schema rows are (type, name, tbl_name, rootpage, sql) and its
+    // b-tree is rooted at page 1.
+    let mut root = None;
+    file.visit_leaf_cells(1, 0, &mut |page, offset| {
+        if root.is_none() {
+            root = file.schema_root(page, offset, table);
+        }
+    })?;
+
+    let mut rows = 0;
+    file.visit_leaf_cells(root?, 0, &mut |_, _| rows += 1)?;
+    Some(rows)
+}
+
+struct File<'a> {
+    db: &'a [u8],
+    page_size: usize,
+}
+
+impl File<'_> {
+    fn page(&self, number: usize) -> Option<&[u8]> {
+        let start = number.checked_sub(1)? * self.page_size;
+        self.db.get(start..start + self.page_size)
+    }
+
+    /// Calls `f` with the page and offset of every cell in the table b-tree
+    /// rooted at `number`.
+    fn visit_leaf_cells(
+        &self,
+        number: usize,
+        depth: usize,
+        f: &mut dyn FnMut(&[u8], usize),
+    ) -> Option<()> {
+        // A corrupt file could loop forever; real trees are never this deep.
+        if depth > 32 {
+            return None;
+        }
+        let page = self.page(number)?;
+        let header = if number == 1 { HEADER_SIZE } else { 0 };
+        let kind = *page.get(header)?;
+        let cells = read_u16(page, header + 3)? as usize;
+        match kind {
+            LEAF_TABLE => {
+                for i in 0..cells {
+                    let offset = read_u16(page, header + 8 + i * 2)? as usize;
+                    f(page, offset);
+                }
+            }
+            INTERIOR_TABLE => {
+                for i in 0..cells {
+                    let offset = read_u16(page, header + 12 + i * 2)? as usize;
+                    let child = read_u32(page, offset)? as usize;
+                    self.visit_leaf_cells(child, depth + 1, f)?;
+                }
+                let right = read_u32(page, header + 8)? as usize;
+                self.visit_leaf_cells(right, depth + 1, f)?;
+            }
+            _ => return None,
+        }
+        Some(())
+    }
+
+    /// Returns the root page of `table` if the schema cell at `offset`
+    /// describes it.
+    fn schema_root(&self, page: &[u8], offset: usize, table: &str) -> Option<usize> {
+        let (_, offset) = read_varint(page, offset)?; // payload size
+        let (_, offset) = read_varint(page, offset)?; // rowid
+        let (header_size, mut cursor) = read_varint(page, offset)?;
+        let header_end = offset + header_size as usize;
+
+        let mut types = Vec::new();
+        while cursor < header_end && types.len() < 4 {
+            let (serial, next) = read_varint(page, cursor)?;
+            types.push(serial);
+            cursor = next;
+        }
+        if types.len() < 4 {
+            return None;
+        }
+
+        let mut body = header_end;
+        let mut values = Vec::new();
+        for serial in types {
+            let size = serial_size(serial)?;
+            values.push((serial, page.get(body..body + size)?));
+            body += size;
+        }
+
+        let (_, kind) = values[0];
+        let (_, name) = values[1];
+        if kind != b"table" || name != table.as_bytes() {
+            return None;
+        }
+        let (serial, root) = values[3];
+        if !(1..=6).contains(&serial) {
+            return None;
+        }
+        Some(root.iter().fold(0usize, |n, &b| n << 8 | b as usize))
+    }
+}
+
+/// Size in bytes of a record value with the given serial type.
+fn serial_size(serial: u64) -> Option<usize> {
+    let size = match serial {
+        0 | 8 | 9 => 0,
+        1..=4 => serial as usize,
+        5 => 6,
+        6 | 7 => 8,
+        n if n >= 12 => ((n - 12) / 2) as usize,
+        _ => return None,
+    };
+    Some(size)
+}
+
+fn read_u16(page: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_be_bytes(page.get(offset..offset + 2)?.try_into().ok()?))
+}
+
+fn read_u32(page: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_be_bytes(page.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+/// Reads a SQLite varint, returning it and the offset just past it.
+fn read_varint(page: &[u8], offset: usize) -> Option<(u64, usize)> {
+    let mut value = 0u64;
+    for i in 0..9 {
+        let byte = *page.get(offset + i)?;
+        if i == 8 {
+            return Some((value << 8 | byte as u64, offset + 9));
+        }
+        value = value << 7 | (byte & 0x7f) as u64;
+        if byte & 0x80 == 0 {
+            return Some((value, offset + i + 1));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SINGLE_PAGE: &[u8] = include_bytes!("fixtures/rpmdb-single-page.sqlite");
+    const INTERIOR: &[u8] = include_bytes!("fixtures/rpmdb-interior.sqlite");
+
+    #[test]
+    fn counts_leaf_root() {
+        assert_eq!(count_rows(SINGLE_PAGE, "Packages"), Some(3));
+        assert_eq!(count_rows(SINGLE_PAGE, "Installtid"), Some(0));
+    }
+
+    #[test]
+    fn counts_through_interior_pages() {
+        assert_eq!(count_rows(INTERIOR, "Packages"), Some(300));
+        assert_eq!(count_rows(INTERIOR, "Installtid"), Some(300));
+    }
+
+    #[test]
+    fn missing_table_or_not_sqlite() {
+        assert_eq!(count_rows(SINGLE_PAGE, "Nope"), None);
+        assert_eq!(count_rows(b"not a database", "Packages"), None);
+        assert_eq!(count_rows(&SINGLE_PAGE[..200], "Packages"), None);
+    }
+
+    #[test]
+    fn varints() {
+        assert_eq!(read_varint(&[0x05], 0), Some((5, 1)));
+        assert_eq!(read_varint(&[0x81, 0x00], 0), Some((128, 2)));
+        assert_eq!(read_varint(&[0xff; 9], 0), Some((u64::MAX, 9)));
+        assert_eq!(read_varint(&[0x81], 0), None);
+    }
+
+    #[test]
+    fn serial_sizes() {
+        assert_eq!(serial_size(0), Some(0));
+        assert_eq!(serial_size(5), Some(6));
+        assert_eq!(serial_size(6), Some(8));
+        assert_eq!(serial_size(13), Some(0));
+        assert_eq!(serial_size(19), Some(3));
+        assert_eq!(serial_size(10), None);
+    }
+}
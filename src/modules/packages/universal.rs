@@ -0,0 +1,200 @@
+//! Package managers that live next to the distro one: app stores, language
+//! toolchains and per-user package sets.
+
+use super::count_dirs;
+use crate::modules::Context;
+use std::collections::HashSet;
+use std::env;
+use std::ffi::OsStr;
+use std::fs;
+use std::path::Path;
+
+/// Homebrew formulae live in `Cellar`, casks in `Caskroom`.
+pub fn count_brew(ctx: &Context) -> Option<usize> {
+    let mut prefixes = vec![
+        ctx.path("/opt/homebrew"),
+        ctx.path("/usr/local"),
+        ctx.path("/home/linuxbrew/.linuxbrew"),
+    ];
+    prefixes.extend(ctx.home_path(".linuxbrew"));
+    sum(prefixes
+        .iter()
+        .flat_map(|prefix| ["Cellar", "Caskroom"].map(|dir| prefix.join(dir)))
+        .filter_map(|dir| count_dirs(&dir)))
+}
+
+/// Installed apps, system-wide and per user. Runtimes are not counted.
+pub fn count_flatpak(ctx: &Context) -> Option<usize> {
+    let mut dirs = vec![ctx.path("/var/lib/flatpak/app")];
+    dirs.extend(ctx.home_path(".local/share/flatpak/app"));
+    sum(dirs.iter().filter_map(|dir| count_dirs(dir)))
+}
+
+/// Every snap is mounted under `/snap/<name>`; `/snap/bin` holds the wrappers.
+pub fn count_snap(ctx: &Context) -> Option<usize> {
+    let count = count_dirs(&ctx.path("/snap"))?;
+    let has_bin = ctx.path("/snap/bin").is_dir();
+    Some(count - usize::from(has_bin))
+}
+
+/// Counts the packages in the system, default and user Nix profiles.
+pub fn count_nix(ctx: &Context) -> Option<usize> {
+    let mut profiles = vec![
+        ctx.path("/run/current-system/sw"),
+        ctx.path("/nix/var/nix/profiles/default"),
+    ];
+    profiles.extend(ctx.home_path(".nix-profile"));
+    profiles.extend(ctx.home_path(".local/state/nix/profiles/profile"));
+    if let Some(user) = env::var_os("USER") {
+        profiles.push(
+            ctx.path("/nix/var/nix/profiles/per-user")
+                .join(user)
+                .join("profile"),
+        );
+    }
+
+    // Several of these are usually symlinks to the same generation.
+    let mut seen = Vec::new();
+    for profile in profiles {
+        if let Ok(target) = fs::canonicalize(&profile) {
+            if !seen.contains(&target) {
+                seen.push(target);
+            }
+        }
+    }
+    sum(seen.iter().filter_map(|profile| count_nix_profile(profile)))
+}
+
+/// `nix profile` writes `manifest.json`, `nix-env` writes `manifest.nix`.
+/// A NixOS system profile has neither, so the store paths its `bin` entries
+/// link into are counted instead.
+fn count_nix_profile(profile: &Path) -> Option<usize> {
+    if let Ok(manifest) = fs::read_to_string(profile.join("manifest.json")) {
+        let manifest: serde_json::Value = serde_json::from_str(&manifest).ok()?;
+        return match &manifest["elements"] {
+            serde_json::Value::Array(elements) => Some(elements.len()),
+            serde_json::Value::Object(elements) => Some(elements.len()),
+            _ => None,
+        };
+    }
+    if let Ok(manifest) = fs::read_to_string(profile.join("manifest.nix")) {
+        return Some(manifest.matches("type = \"derivation\";").count());
+    }
+    count_store_paths(&profile.join("bin"))
+}
+
+/// Distinct packages the links in `dir` point into. A profile where a single
+/// package provides `bin` links the directory itself.
+fn count_store_paths(dir: &Path) -> Option<usize> {
+    if let Ok(target) = fs::read_link(dir) {
+        return Some(usize::from(store_path(&target).is_some()));
+    }
+    let mut packages = HashSet::new();
+    for entry in fs::read_dir(dir).ok()?.flatten() {
+        if let Ok(target) = fs::read_link(entry.path()) {
+            packages.extend(store_path(&target).map(OsStr::to_os_string));
+        }
+    }
+    Some(packages.len())
+}
+
+/// The `<hash>-<name>` part of a path inside `/nix/store`.
+fn store_path(path: &Path) -> Option<&OsStr> {
+    let rest = path.strip_prefix("/nix/store").ok()?;
+    rest.components().next().map(|part| part.as_os_str())
+}
+
+/// Crates installed with `cargo install`, from cargo's own install tracker.
+pub fn count_cargo(ctx: &Context) -> Option<usize> {
+    let cargo_home = match env::var_os("CARGO_HOME") {
+        Some(dir) => ctx.path(&dir.to_string_lossy()),
+        None => ctx.home_path(".cargo")?,
+    };
+    let crates = fs::read_to_string(cargo_home.join(".crates2.json")).ok()?;
+    let crates: serde_json::Value = serde_json::from_str(&crates).ok()?;
+    crates["installs"].as_object().map(|installs| installs.len())
+}
+
+/// pipx gives every application its own venv.
+pub fn count_pipx(ctx: &Context) -> Option<usize> {
+    let venvs = match env::var_os("PIPX_HOME") {
+        Some(dir) => ctx.path(&dir.to_string_lossy()).join("venvs"),
+        None => ctx.home_path(".local/share/pipx/venvs")?,
+    };
+    count_dirs(&venvs).or_else(|| count_dirs(&ctx.home_path(".local/pipx/venvs")?))
+}
+
+/// Globally installed npm packages, counting each package of a `@scope`.
+pub fn count_npm(ctx: &Context) -> Option<usize> {
+    let mut prefixes = vec![ctx.path("/usr/lib"), ctx.path("/usr/local/lib")];
+    if let Some(prefix) = env::var_os("NPM_CONFIG_PREFIX") {
+        prefixes.push(ctx.path(&prefix.to_string_lossy()).join("lib"));
+    }
+    prefixes.extend(ctx.home_path(".npm-global/lib"));
+    sum(prefixes
+        .iter()
+        .filter_map(|prefix| count_node_modules(&prefix.join("node_modules"))))
+}
+
+fn count_node_modules(dir: &Path) -> Option<usize> {
+    let entries = fs::read_dir(dir).ok()?;
+    let mut count = 0;
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if name.starts_with('.') {
+            continue;
+        }
+        if name.starts_with('@') {
+            count += count_dirs(&entry.path()).unwrap_or(0);
+        } else {
+            count += 1;
+        }
+    }
+    Some(count)
+}
+
+/// Adds up the sources that exist, `None` if none of them do.
+fn sum(counts: impl Iterator<Item = usize>) -> Option<usize> {
+    counts.reduce(|a, b| a + b)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::modules::fixture::Fixture;
+
+    #[test]
+    fn nix_counts_system_and_default_profiles() {
+        let fixture = Fixture::new();
+        fixture
+            .symlink(
+                "/run/current-system/sw/bin/bash",
+                "/nix/store/aaa-bash-5.2/bin/bash",
+            )
+            .symlink(
+                "/run/current-system/sw/bin/sh",
+                "/nix/store/aaa-bash-5.2/bin/sh",
+            )
+            .symlink(
+                "/run/current-system/sw/bin/ls",
+                "/nix/store/bbb-coreutils-9.5/bin/ls",
+            )
+            .file("/run/current-system/sw/bin/not-a-link", "")
+            .file(
+                "/nix/var/nix/profiles/default/manifest.json",
+                r#"{"version": 2, "elements": [{}, {}, {}]}"#,
+            );
+        assert_eq!(count_nix(&fixture.context()), Some(5));
+    }
+
+    #[test]
+    fn nix_profile_with_linked_bin() {
+        let fixture = Fixture::new();
+        fixture.symlink(
+            "/run/current-system/sw/bin",
+            "/nix/store/ccc-hello-2.12/bin",
+        );
+        assert_eq!(count_nix(&fixture.context()), Some(1));
+    }
+}
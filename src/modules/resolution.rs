@@ -0,0 +1,313 @@
+use super::{command, Context, Module, ModuleError, Value};
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+pub struct Resolution;
+
+impl Module for Resolution {
+    fn name(&self) -> &'static str {
+        "resolution"
+    }
+
+    fn label(&self) -> &'static str {
+        "Resolution"
+    }
+
+    fn description(&self) -> &'static str {
+        "Resolution and refresh rate of each connected display"
+    }
+
+    fn collect(&self, ctx: &Context) -> Result<Value, ModuleError> {
+        let monitors = get_monitors(ctx, ctx.timeout(self.name()));
+        if monitors.is_empty() {
+            return Err(ModuleError::Unavailable);
+        }
+        Ok(Value::Lines(
+            monitors.iter().map(Monitor::to_string).collect(),
+        ))
+    }
+}
+
+struct Monitor {
+    connector: Option<String>,
+    width: u32,
+    height: u32,
+    refresh: Option<f64>,
+}
+
+impl fmt::Display for Monitor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}x{}", self.width, self.height)?;
+        if let Some(refresh) = self.refresh {
+            write!(f, " @ {}Hz", refresh.round())?;
+        }
+        if let Some(connector) = &self.connector {
+            write!(f, " ({})", connector)?;
+        }
+        Ok(())
+    }
+}
+
+fn get_monitors(ctx: &Context, timeout: Duration) -> Vec<Monitor> {
+    if cfg!(target_os = "linux") {
+        // sysfs needs no subprocess, so the session tools are only asked
+        // when it has nothing, e.g. with drivers that don't use KMS.
+        let monitors = drm_displays(ctx);
+        if !monitors.is_empty() {
+            return monitors;
+        }
+        if env::var_os("WAYLAND_DISPLAY").is_some() {
+            let monitors = command::stdout(&mut Command::new("wlr-randr"), timeout)
+                .map(|stdout| parse_wlr_randr(&stdout))
+                .unwrap_or_default();
+            if !monitors.is_empty() {
+                return monitors;
+            }
+        }
+        if env::var_os("DISPLAY").is_some() {
+            if let Some(stdout) = command::stdout(Command::new("xrandr").arg("--current"), timeout)
+            {
+                return parse_xrandr(&stdout);
+            }
+        }
+    } else if cfg!(target_os = "macos") {
+        if let Some(stdout) = command::stdout(
+            Command::new("system_profiler").arg("SPDisplaysDataType"),
+            timeout,
+        ) {
+            return stdout
+                .lines()
+                .filter_map(|line| line.trim().strip_prefix("Resolution:"))
+                .filter_map(|res| {
+                    let mut parts = res.split_whitespace();
+                    let width = parts.next()?.parse().ok()?;
+                    parts.next(); // "x"
+                    let height = parts.next()?.parse().ok()?;
+                    Some(Monitor {
+                        connector: None,
+                        width,
+                        height,
+                        refresh: None,
+                    })
+                })
+                .collect();
+        }
+    }
+    Vec::new()
+}
+
+/// Reads connected connectors from `/sys/class/drm`. `modes` lists the
+/// probed modes with the preferred one first, so this is the monitor's
+/// native mode, not necessarily the one in use; sysfs does not expose that.
+/// The refresh rate comes from the EDID's preferred timing.
+fn drm_displays(ctx: &Context) -> Vec<Monitor> {
+    let Ok(entries) = fs::read_dir(ctx.path("/sys/class/drm")) else {
+        return Vec::new();
+    };
+    let mut connectors: Vec<_> = entries.flatten().map(|entry| entry.path()).collect();
+    connectors.sort();
+
+    let mut displays = Vec::new();
+    for dir in connectors {
+        let Some(name) = dir
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+        else {
+            continue;
+        };
+        // Connectors are `card<N>-<connector>`, the bare `card<N>` is the GPU.
+        let Some((_, connector)) = name.split_once('-') else {
+            continue;
+        };
+        let connected =
+            fs::read_to_string(dir.join("status")).is_ok_and(|status| status.trim() == "connected");
+        if !connected {
+            continue;
+        }
+        let Ok(modes) = fs::read_to_string(dir.join("modes")) else {
+            continue;
+        };
+        let Some((width, height)) = modes.lines().next().and_then(parse_size) else {
+            continue;
+        };
+        let refresh = edid_refresh(&dir.join("edid"), width, height);
+        displays.push(Monitor {
+            connector: Some(connector.to_string()),
+            width,
+            height,
+            refresh,
+        });
+    }
+    displays
+}
+
+/// Refresh rate of the EDID's preferred timing, if it is `width`x`height`.
+fn edid_refresh(path: &Path, width: u32, height: u32) -> Option<f64> {
+    let edid = fs::read(path).ok()?;
+    let dtd = edid.get(54..72)?;
+    let pixel_clock = u16::from_le_bytes([dtd[0], dtd[1]]) as f64 * 10_000.0;
+    if pixel_clock == 0.0 {
+        return None;
+    }
+    let h_active = dtd[2] as u32 | (dtd[4] as u32 & 0xf0) << 4;
+    let h_blank = dtd[3] as u32 | (dtd[4] as u32 & 0x0f) << 8;
+    let v_active = dtd[5] as u32 | (dtd[7] as u32 & 0xf0) << 4;
+    let v_blank = dtd[6] as u32 | (dtd[7] as u32 & 0x0f) << 8;
+    if (h_active, v_active) != (width, height) {
+        return None;
+    }
+    Some(pixel_clock / ((h_active + h_blank) * (v_active + v_blank)) as f64)
+}
+
+/// Parses `xrandr --current`; the mode in use is marked with `*`.
+fn parse_xrandr(stdout: &str) -> Vec<Monitor> {
+    let mut displays = Vec::new();
+    let mut connector = None;
+    for line in stdout.lines() {
+        if !line.starts_with(' ') {
+            connector = line
+                .contains(" connected")
+                .then(|| line.split_whitespace().next().map(str::to_string))
+                .flatten();
+            continue;
+        }
+        let Some(name) = &connector else {
+            continue;
+        };
+        let mut parts = line.split_whitespace();
+        let Some((width, height)) = parts.next().and_then(parse_size) else {
+            continue;
+        };
+        let Some(rate) = parts.find(|rate| rate.contains('*')) else {
+            continue;
+        };
+        displays.push(Monitor {
+            connector: Some(name.clone()),
+            width,
+            height,
+            refresh: rate.trim_end_matches(['*', '+']).parse().ok(),
+        });
+    }
+    displays
+}
+
+/// Parses `wlr-randr`, whose mode in use is tagged `current`.
+fn parse_wlr_randr(stdout: &str) -> Vec<Monitor> {
+    let mut displays = Vec::new();
+    let mut connector = None;
+    for line in stdout.lines() {
+        if !line.starts_with(' ') {
+            connector = line.split_whitespace().next().map(str::to_string);
+            continue;
+        }
+        // "    1920x1080 px, 60.000000 Hz (preferred, current)"
+        if !line.contains("current") {
+            continue;
+        }
+        let mut parts = line.split_whitespace();
+        let Some((width, height)) = parts.next().and_then(parse_size) else {
+            continue;
+        };
+        let refresh = parts.nth(1).and_then(|rate| rate.parse().ok());
+        displays.push(Monitor {
+            connector: connector.clone(),
+            width,
+            height,
+            refresh,
+        });
+    }
+    displays
+}
+
+/// Parses `1920x1080`, ignoring suffixes such as the `i` of interlaced modes.
+fn parse_size(mode: &str) -> Option<(u32, u32)> {
+    let (width, height) = mode.trim().split_once('x')?;
+    let height: String = height.chars().take_while(char::is_ascii_digit).collect();
+    Some((width.parse().ok()?, height.parse().ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::fixture::Fixture;
+
+    fn describe(monitors: Vec<Monitor>) -> Vec<String> {
+        monitors.iter().map(Monitor::to_string).collect()
+    }
+
+    #[test]
+    fn drm_preferred_modes() {
+        let fixture = Fixture::new();
+        fixture
+            .dir("/sys/class/drm/card0")
+            .file("/sys/class/drm/card0-DP-1/status", "connected\n")
+            .file("/sys/class/drm/card0-DP-1/modes", "2560x1440\n1920x1080\n")
+            .file("/sys/class/drm/card0-HDMI-A-1/status", "disconnected\n")
+            .file("/sys/class/drm/card0-HDMI-A-1/modes", "");
+        assert_eq!(
+            describe(drm_displays(&fixture.context())),
+            ["2560x1440 (DP-1)"]
+        );
+    }
+
+    #[test]
+    fn xrandr_current_modes() {
+        let stdout = "\
+Screen 0: minimum 320 x 200, current 4480 x 1440, maximum 16384 x 16384
+DP-1 connected primary 2560x1440+0+0 (normal left inverted right x axis y axis) 597mm x 336mm
+   2560x1440     59.95 + 143.97*
+   1920x1080     60.00    50.00
+HDMI-1 disconnected (normal left inverted right x axis y axis)
+   1920x1080     60.00
+eDP-1 connected 1920x1080+2560+0 (normal left inverted right x axis y axis) 309mm x 174mm
+   1920x1080     60.02*+
+   1920x1080i    60.00
+";
+        assert_eq!(
+            describe(parse_xrandr(stdout)),
+            ["2560x1440 @ 144Hz (DP-1)", "1920x1080 @ 60Hz (eDP-1)"]
+        );
+    }
+
+    #[test]
+    fn xrandr_without_active_mode() {
+        let stdout = "\
+DP-2 connected (normal left inverted right x axis y axis)
+   3840x2160     60.00 +  30.00
+";
+        assert!(parse_xrandr(stdout).is_empty());
+    }
+
+    #[test]
+    fn wlr_randr_current_modes() {
+        let stdout = "\
+DP-1 \"Dell Inc. DELL U2720Q (DP-1)\"
+  Enabled: yes
+  Modes:
+    3840x2160 px, 60.000000 Hz (preferred)
+    2560x1440 px, 59.951000 Hz (current)
+  Position: 0,0
+HDMI-A-1 \"LG Electronics LG ULTRAGEAR (HDMI-A-1)\"
+  Enabled: yes
+  Modes:
+    1920x1080 px, 144.001007 Hz (preferred, current)
+    1920x1080 px, 60.000000 Hz
+";
+        assert_eq!(
+            describe(parse_wlr_randr(stdout)),
+            ["2560x1440 @ 60Hz (DP-1)", "1920x1080 @ 144Hz (HDMI-A-1)"]
+        );
+    }
+
+    #[test]
+    fn sizes() {
+        assert_eq!(parse_size("1920x1080"), Some((1920, 1080)));
+        assert_eq!(parse_size("1920x1080i"), Some((1920, 1080)));
+        assert_eq!(parse_size(" 800x600\n"), Some((800, 600)));
+        assert_eq!(parse_size("preferred"), None);
+    }
+}
@@ -0,0 +1,151 @@
+use super::{command, Context, Module, ModuleError, Refresh, Value};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time::UNIX_EPOCH;
+
+/// Shell process names, and the variable that holds the version when the
+/// shell exports it. Shells marked `true` answer `--version`.
+const SHELLS: &[(&str, Option<&str>, bool)] = &[
+    ("bash", Some("BASH_VERSION"), true),
+    ("zsh", Some("ZSH_VERSION"), true),
+    ("fish", Some("FISH_VERSION"), true),
+    ("nu", None, true),
+    ("elvish", None, true),
+    ("xonsh", None, true),
+    ("pwsh", None, true),
+    ("tcsh", None, true),
+    ("ksh", None, true),
+    ("mksh", Some("KSH_VERSION"), false),
+    ("oksh", None, false),
+    ("yash", None, true),
+    ("ion", None, true),
+    ("csh", None, false),
+    ("dash", None, false),
+    ("ash", None, false),
+    ("sh", None, false),
+];
+
+pub struct Shell;
+
+impl Module for Shell {
+    fn name(&self) -> &'static str {
+        "shell"
+    }
+
+    fn label(&self) -> &'static str {
+        "Shell"
+    }
+
+    fn description(&self) -> &'static str {
+        "Shell nekofetch was started from, with its version"
+    }
+
+    fn refreshes(&self) -> &'static [Refresh] {
+        &[Refresh::Processes]
+    }
+
+    fn collect(&self, ctx: &Context) -> Result<Value, ModuleError> {
+        // The closest shell above us, not necessarily the login shell.
+        let running = ctx.ancestors().into_iter().find_map(|process| {
+            let name = process.name().to_string_lossy();
+            let name = name.trim_start_matches('-'); // login shells
+            let shell = SHELLS.iter().find(|(known, _, _)| *known == name)?;
+            Some((shell, process.exe().map(Path::to_path_buf)))
+        });
+
+        let Some(((name, version_var, answers_version), exe)) = running else {
+            // Fall back to the login shell.
+            return env::var("SHELL")
+                .or_else(|_| env::var("ComSpec"))
+                .map(|shell| short_name(&shell).into())
+                .map_err(|_| ModuleError::Unavailable);
+        };
+
+        let version = version_var
+            .and_then(|var| env::var(var).ok())
+            .map(|version| clean_version(&version))
+            .or_else(|| match exe {
+                Some(exe) if *answers_version => probe_version(ctx, &exe, self.name()),
+                _ => None,
+            });
+        Ok(match version {
+            Some(version) => format!("{} {}", name, version),
+            None => name.to_string(),
+        }
+        .into())
+    }
+}
+
+fn short_name(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map_or(path.to_string(), |name| name.to_string_lossy().into_owned())
+}
+
+/// Runs `<exe> --version`, remembering the answer per binary and mtime in
+/// the cache dir so later runs don't spawn the shell again.
+fn probe_version(ctx: &Context, exe: &Path, module: &str) -> Option<String> {
+    let modified = fs::metadata(exe)
+        .and_then(|meta| meta.modified())
+        .ok()?
+        .duration_since(UNIX_EPOCH)
+        .ok()?
+        .as_secs();
+    let key = format!("{}\t{}", exe.display(), modified);
+
+    let cache_path = cache_path();
+    let cache = cache_path
+        .as_ref()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .unwrap_or_default();
+    let cached = cache.lines().find_map(|line| {
+        let (entry, version) = line.rsplit_once('\t')?;
+        (entry == key).then(|| version.to_string())
+    });
+    if cached.is_some() {
+        return cached;
+    }
+
+    let stdout = command::stdout(Command::new(exe).arg("--version"), ctx.timeout(module))?;
+    let version = parse_version(&stdout)?;
+
+    if let Some(path) = cache_path {
+        // Drop stale entries for the same binary before adding the new one.
+        let prefix = format!("{}\t", exe.display());
+        let mut lines: Vec<&str> = cache
+            .lines()
+            .filter(|line| !line.starts_with(&prefix))
+            .collect();
+        let entry = format!("{}\t{}", key, version);
+        lines.push(&entry);
+        if let Some(dir) = path.parent() {
+            let _ = fs::create_dir_all(dir);
+        }
+        let _ = fs::write(&path, lines.join("\n") + "\n");
+    }
+    Some(version)
+}
+
+fn cache_path() -> Option<PathBuf> {
+    Some(dirs::cache_dir()?.join("nekofetch/shell_versions"))
+}
+
+/// First dotted number in `--version` output, e.g. `5.2.15` out of
+/// `GNU bash, version 5.2.15(1)-release` or `1.0.4` out of `93u+m/1.0.4`.
+fn parse_version(stdout: &str) -> Option<String> {
+    stdout
+        .split(|c: char| c.is_whitespace() || c == '/' || c == ',')
+        .find(|token| token.starts_with(|c: char| c.is_ascii_digit()) && token.contains('.'))
+        .map(clean_version)
+}
+
+fn clean_version(version: &str) -> String {
+    version
+        .split(|c: char| !c.is_ascii_digit() && c != '.')
+        .next()
+        .unwrap_or(version)
+        .trim_end_matches('.')
+        .to_string()
+}
@@ -0,0 +1,148 @@
+use super::{Context, Module, ModuleError, Value};
+use crate::units;
+use std::collections::HashMap;
+use sysinfo::Disks;
+
+/// Filesystems that never hold user data worth listing.
+const PSEUDO_FS_TYPES: &[&str] = &[
+    "tmpfs",
+    "devtmpfs",
+    "ramfs",
+    "overlay",
+    "squashfs",
+    "proc",
+    "sysfs",
+    "devfs",
+    "autofs",
+    "efivarfs",
+    "fuse.portal",
+    "fuse.snapfuse",
+];
+
+pub struct Storage;
+
+impl Module for Storage {
+    fn name(&self) -> &'static str {
+        "storage"
+    }
+
+    fn label(&self) -> &'static str {
+        "Storage"
+    }
+
+    fn description(&self) -> &'static str {
+        "Used and total space of each mounted filesystem"
+    }
+
+    fn collect(&self, ctx: &Context) -> Result<Value, ModuleError> {
+        let storage_info = get_storage_info(ctx);
+        if storage_info.is_empty() {
+            return Err(ModuleError::Unavailable);
+        }
+        Ok(Value::Lines(storage_info))
+    }
+}
+
+struct Filesystem {
+    device: String,
+    mount: String,
+    fs_type: String,
+    total: u64,
+    available: u64,
+}
+
+fn get_storage_info(ctx: &Context) -> Vec<String> {
+    let disks = Disks::new_with_refreshed_list();
+    let filesystems = disks.list().iter().map(|disk| Filesystem {
+        device: disk.name().to_string_lossy().into_owned(),
+        mount: disk.mount_point().to_string_lossy().into_owned(),
+        fs_type: disk.file_system().to_string_lossy().into_owned(),
+        total: disk.total_space(),
+        available: disk.available_space(),
+    });
+
+    let filesystems = filesystems.filter(|fs| {
+        !PSEUDO_FS_TYPES.contains(&fs.fs_type.as_str())
+            && ctx.config.storage.allows(&fs.mount, &fs.fs_type)
+    });
+    dedupe(filesystems)
+        .iter()
+        .map(|fs| {
+            let used_space = fs.total.saturating_sub(fs.available);
+            format!(
+                "{} ({}): {}",
+                fs.mount,
+                fs.fs_type,
+                units::format_usage(used_space, fs.total, &ctx.config.units)
+            )
+        })
+        .collect()
+}
+
+/// Bind mounts and subvolumes show up once per mount point; keeps the
+/// shortest mount point for each device. Only device paths are compared:
+/// on Windows the name is the volume label, which is often empty or
+/// shared ("Local Disk").
+fn dedupe(filesystems: impl Iterator<Item = Filesystem>) -> Vec<Filesystem> {
+    let mut by_device: HashMap<String, Filesystem> = HashMap::new();
+    for fs in filesystems {
+        let key = if fs.device.starts_with('/') {
+            fs.device.clone()
+        } else {
+            fs.mount.clone()
+        };
+        match by_device.get(&key) {
+            Some(seen) if seen.mount.len() <= fs.mount.len() => {}
+            _ => {
+                by_device.insert(key, fs);
+            }
+        }
+    }
+
+    let mut filesystems: Vec<Filesystem> = by_device.into_values().collect();
+    filesystems.sort_by(|a, b| a.mount.cmp(&b.mount));
+    filesystems
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fs(device: &str, mount: &str) -> Filesystem {
+        Filesystem {
+            device: device.to_string(),
+            mount: mount.to_string(),
+            fs_type: "ext4".to_string(),
+            total: 0,
+            available: 0,
+        }
+    }
+
+    fn mounts(filesystems: Vec<Filesystem>) -> Vec<String> {
+        dedupe(filesystems.into_iter())
+            .into_iter()
+            .map(|fs| fs.mount)
+            .collect()
+    }
+
+    #[test]
+    fn bind_mounts_keep_shortest_mount_point() {
+        let filesystems = vec![
+            fs("/dev/nvme0n1p2", "/home"),
+            fs("/dev/nvme0n1p2", "/"),
+            fs("/dev/nvme0n1p1", "/boot"),
+        ];
+        assert_eq!(mounts(filesystems), ["/", "/boot"]);
+    }
+
+    #[test]
+    fn volume_labels_are_not_deduped() {
+        let filesystems = vec![
+            fs("Local Disk", "C:\\"),
+            fs("Local Disk", "D:\\"),
+            fs("", "E:\\"),
+            fs("", "F:\\"),
+        ];
+        assert_eq!(mounts(filesystems), ["C:\\", "D:\\", "E:\\", "F:\\"]);
+    }
+}
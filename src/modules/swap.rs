@@ -0,0 +1,52 @@
+use super::{meminfo, Context, Module, ModuleError, Refresh, Value};
+use crate::units;
+
+pub struct Swap;
+
+impl Module for Swap {
+    fn name(&self) -> &'static str {
+        "swap"
+    }
+
+    fn label(&self) -> &'static str {
+        "Swap"
+    }
+
+    fn description(&self) -> &'static str {
+        "Used and total swap space, per device in detailed memory mode"
+    }
+
+    fn refreshes(&self) -> &'static [Refresh] {
+        &[Refresh::Memory]
+    }
+
+    fn collect(&self, ctx: &Context) -> Result<Value, ModuleError> {
+        if ctx.config.memory.detailed {
+            let devices = meminfo::swap_devices(ctx);
+            if !devices.is_empty() {
+                let lines = devices
+                    .iter()
+                    .map(|device| {
+                        let mut line = format!(
+                            "{} ({}): {}",
+                            device.name,
+                            device.kind,
+                            units::format_usage(device.used, device.size, &ctx.config.units)
+                        );
+                        if let Some(ratio) = device.compression {
+                            line.push_str(&format!(", {:.1}x compression", ratio));
+                        }
+                        line
+                    })
+                    .collect();
+                return Ok(Value::Lines(lines));
+            }
+        }
+
+        let total_swap = ctx.sys.total_swap();
+        if total_swap == 0 {
+            return Err(ModuleError::NotPresent);
+        }
+        Ok(units::format_usage(ctx.sys.used_swap(), total_swap, &ctx.config.units).into())
+    }
+}
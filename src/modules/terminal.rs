@@ -0,0 +1,126 @@
+use super::{command, Context, Module, ModuleError, Refresh, Value};
+use std::env;
+use std::process::Command;
+
+/// Terminal emulator process names, the name to show, and the binary that
+/// answers `--version` (the process itself is sometimes a server without one).
+const TERMINALS: &[(&str, &str, Option<&str>)] = &[
+    ("kitty", "kitty", Some("kitty")),
+    ("alacritty", "Alacritty", Some("alacritty")),
+    ("wezterm-gui", "WezTerm", Some("wezterm")),
+    ("ghostty", "Ghostty", Some("ghostty")),
+    ("foot", "foot", Some("foot")),
+    ("footclient", "foot", Some("foot")),
+    ("konsole", "Konsole", Some("konsole")),
+    ("yakuake", "Yakuake", Some("yakuake")),
+    ("gnome-terminal-", "GNOME Terminal", Some("gnome-terminal")),
+    ("kgx", "GNOME Console", Some("kgx")),
+    ("xfce4-terminal", "Xfce Terminal", Some("xfce4-terminal")),
+    ("mate-terminal", "MATE Terminal", Some("mate-terminal")),
+    ("lxterminal", "LXTerminal", Some("lxterminal")),
+    ("qterminal", "QTerminal", Some("qterminal")),
+    ("terminator", "Terminator", Some("terminator")),
+    ("tilix", "Tilix", Some("tilix")),
+    ("terminology", "Terminology", Some("terminology")),
+    ("rio", "Rio", Some("rio")),
+    ("contour", "Contour", Some("contour")),
+    ("sakura", "Sakura", Some("sakura")),
+    ("guake", "Guake", Some("guake")),
+    ("xterm", "xterm", None),
+    ("urxvt", "urxvt", None),
+    ("rxvt", "rxvt", None),
+    ("st", "st", None),
+    ("iTerm2", "iTerm2", None),
+    ("Terminal", "Apple Terminal", None),
+    ("WindowsTerminal", "Windows Terminal", None),
+    ("code", "VS Code", None),
+];
+
+/// Processes that sit between a terminal and its shell and hide it from the
+/// tree; shown when no terminal is found above them.
+const MULTIPLEXERS: &[(&str, &str)] = &[
+    ("tmux: server", "tmux"),
+    ("tmux", "tmux"),
+    ("screen", "screen"),
+    ("SCREEN", "screen"),
+    ("zellij", "zellij"),
+    ("sshd", "SSH"),
+];
+
+pub struct Terminal;
+
+impl Module for Terminal {
+    fn name(&self) -> &'static str {
+        "terminal"
+    }
+
+    fn label(&self) -> &'static str {
+        "Terminal"
+    }
+
+    fn description(&self) -> &'static str {
+        "Terminal emulator and its version"
+    }
+
+    fn refreshes(&self) -> &'static [Refresh] {
+        &[Refresh::Processes]
+    }
+
+    fn collect(&self, ctx: &Context) -> Result<Value, ModuleError> {
+        let mut fallback = None;
+        for process in ctx.ancestors() {
+            let name = process.name().to_string_lossy();
+            if let Some((_, display, version_cmd)) = TERMINALS
+                .iter()
+                .find(|(known, _, _)| terminal_matches(known, &name))
+            {
+                let version = env_version(display)
+                    .or_else(|| version_cmd.and_then(|cmd| command_version(ctx, cmd, self.name())));
+                return Ok(match version {
+                    Some(version) => format!("{} {}", display, version),
+                    None => display.to_string(),
+                }
+                .into());
+            }
+            if fallback.is_none() {
+                fallback = MULTIPLEXERS
+                    .iter()
+                    .find(|(known, _)| name == *known)
+                    .map(|(_, display)| display.to_string());
+            }
+        }
+
+        fallback
+            .or_else(|| env::var("TERM_PROGRAM").ok())
+            .or_else(|| env::var("TERM").ok())
+            .map(Value::Text)
+            .ok_or(ModuleError::Unavailable)
+    }
+}
+
+/// A trailing `-` matches any suffix, as in `gnome-terminal-server`.
+fn terminal_matches(known: &str, name: &str) -> bool {
+    if known.ends_with('-') {
+        name.starts_with(known)
+    } else {
+        name == known
+    }
+}
+
+/// Terminals that set `TERM_PROGRAM` usually publish their version next to it.
+fn env_version(display: &str) -> Option<String> {
+    let program = env::var("TERM_PROGRAM").ok()?;
+    if !display.eq_ignore_ascii_case(&program) && !program.contains(display) {
+        return None;
+    }
+    env::var("TERM_PROGRAM_VERSION").ok()
+}
+
+/// First token of `<cmd> --version` that looks like a version number.
+fn command_version(ctx: &Context, cmd: &str, module: &str) -> Option<String> {
+    let stdout = command::stdout(Command::new(cmd).arg("--version"), ctx.timeout(module))?;
+    stdout
+        .split_whitespace()
+        .find(|token| token.starts_with(|c: char| c.is_ascii_digit()) && token.contains('.'))
+        .map(|token| token.trim_end_matches([',', ')']).to_string())
+}
@@ -0,0 +1,276 @@
+//! Look-and-feel modules: GTK theme, Qt style, icons, cursor and font.
+//!
+//! Settings are read from the GTK `settings.ini` files, `~/.gtkrc-2.0`, KDE's
+//! `kdeglobals`/`kcminputrc`, qt5ct/qt6ct and GNOME's dconf database. Each
+//! module shows every distinct value with the toolkits that use it, e.g.
+//! `Adwaita-dark [GTK3/4], Breeze [Qt]`.
+
+use super::{command, Context, Module, ModuleError, Value};
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+/// Everything the theme modules read, loaded once per `Context` and shared
+/// between them.
+#[derive(Default)]
+pub(super) struct Settings {
+    gtk2: String,
+    gtk3: String,
+    gtk4: String,
+    kdeglobals: String,
+    kcminputrc: String,
+    qt5ct: String,
+    qt6ct: String,
+    /// `dconf dump /org/gnome/desktop/interface/`.
+    gnome: String,
+}
+
+impl Settings {
+    pub(super) fn load(ctx: &Context) -> Settings {
+        let read = |path: Option<PathBuf>| {
+            path.and_then(|path| fs::read_to_string(path).ok())
+                .unwrap_or_default()
+        };
+        Settings {
+            gtk2: read(ctx.home_path(".gtkrc-2.0")),
+            gtk3: read(ctx.config_path("gtk-3.0/settings.ini")),
+            gtk4: read(ctx.config_path("gtk-4.0/settings.ini")),
+            kdeglobals: read(ctx.config_path("kdeglobals")),
+            kcminputrc: read(ctx.config_path("kcminputrc")),
+            qt5ct: read(ctx.config_path("qt5ct/qt5ct.conf")),
+            qt6ct: read(ctx.config_path("qt6ct/qt6ct.conf")),
+            gnome: command::stdout(
+                Command::new("dconf").args(["dump", "/org/gnome/desktop/interface/"]),
+                ctx.timeout("theme"),
+            )
+            .unwrap_or_default(),
+        }
+    }
+
+    /// Looks `key` up in the GTK files and `gnome_key` in dconf. GNOME
+    /// settings apply to GTK 3 and 4 unless a `settings.ini` overrides them.
+    fn gtk(&self, key: &str, gnome_key: &str) -> Vec<(&'static str, String)> {
+        let gnome = ini_value(&self.gnome, "/", gnome_key);
+        let mut values = Vec::new();
+        values.extend(ini_value(&self.gtk2, "", key).map(|v| ("GTK2", v)));
+        values.extend(
+            ini_value(&self.gtk3, "Settings", key)
+                .or_else(|| gnome.clone())
+                .map(|v| ("GTK3", v)),
+        );
+        values.extend(
+            ini_value(&self.gtk4, "Settings", key)
+                .or(gnome)
+                .map(|v| ("GTK4", v)),
+        );
+        values
+    }
+}
+
+pub struct Theme;
+
+impl Module for Theme {
+    fn name(&self) -> &'static str {
+        "theme"
+    }
+
+    fn label(&self) -> &'static str {
+        "Theme"
+    }
+
+    fn description(&self) -> &'static str {
+        "GTK theme, or the appearance on macOS"
+    }
+
+    fn collect(&self, ctx: &Context) -> Result<Value, ModuleError> {
+        if cfg!(target_os = "macos") {
+            // The key only exists while dark mode is on.
+            let style = command::stdout(
+                Command::new("defaults").args(["read", "-g", "AppleInterfaceStyle"]),
+                ctx.timeout(self.name()),
+            );
+            let style = style.map_or("Light".to_string(), |s| s.trim().to_string());
+            return Ok(style.into());
+        }
+        let settings = ctx.theme_settings();
+        render(settings.gtk("gtk-theme-name", "gtk-theme"))
+    }
+}
+
+pub struct QtStyle;
+
+impl Module for QtStyle {
+    fn name(&self) -> &'static str {
+        "qt_style"
+    }
+
+    fn label(&self) -> &'static str {
+        "Qt Style"
+    }
+
+    fn description(&self) -> &'static str {
+        "Qt widget style from KDE, qt5ct or qt6ct"
+    }
+
+    fn collect(&self, ctx: &Context) -> Result<Value, ModuleError> {
+        let settings = ctx.theme_settings();
+        let mut values = Vec::new();
+        values.extend(ini_value(&settings.kdeglobals, "KDE", "widgetStyle").map(|v| ("KDE", v)));
+        values.extend(ini_value(&settings.qt5ct, "Appearance", "style").map(|v| ("Qt5", v)));
+        values.extend(ini_value(&settings.qt6ct, "Appearance", "style").map(|v| ("Qt6", v)));
+        render(values)
+    }
+}
+
+pub struct Icons;
+
+impl Module for Icons {
+    fn name(&self) -> &'static str {
+        "icons"
+    }
+
+    fn label(&self) -> &'static str {
+        "Icons"
+    }
+
+    fn description(&self) -> &'static str {
+        "Icon theme"
+    }
+
+    fn collect(&self, ctx: &Context) -> Result<Value, ModuleError> {
+        let settings = ctx.theme_settings();
+        let mut values = settings.gtk("gtk-icon-theme-name", "icon-theme");
+        values.extend(ini_value(&settings.kdeglobals, "Icons", "Theme").map(|v| ("KDE", v)));
+        render(values)
+    }
+}
+
+pub struct Cursor;
+
+impl Module for Cursor {
+    fn name(&self) -> &'static str {
+        "cursor"
+    }
+
+    fn label(&self) -> &'static str {
+        "Cursor"
+    }
+
+    fn description(&self) -> &'static str {
+        "Cursor theme"
+    }
+
+    fn collect(&self, ctx: &Context) -> Result<Value, ModuleError> {
+        let settings = ctx.theme_settings();
+        let mut values = settings.gtk("gtk-cursor-theme-name", "cursor-theme");
+        values.extend(ini_value(&settings.kcminputrc, "Mouse", "cursorTheme").map(|v| ("KDE", v)));
+        render(values)
+    }
+}
+
+pub struct Font;
+
+impl Module for Font {
+    fn name(&self) -> &'static str {
+        "font"
+    }
+
+    fn label(&self) -> &'static str {
+        "Font"
+    }
+
+    fn description(&self) -> &'static str {
+        "User interface font"
+    }
+
+    fn collect(&self, ctx: &Context) -> Result<Value, ModuleError> {
+        let settings = ctx.theme_settings();
+        let mut values = settings.gtk("gtk-font-name", "font-name");
+        // Qt stores fonts as "Family,size,..." .
+        let kde_font = ini_value(&settings.kdeglobals, "General", "font").and_then(|font| {
+            let mut parts = font.split(',');
+            Some(format!("{} {}", parts.next()?, parts.next()?))
+        });
+        values.extend(kde_font.map(|v| ("KDE", v)));
+        render(values)
+    }
+}
+
+/// Reads `key` from `section` of an INI-style file; `""` is the part before
+/// the first section header. Surrounding quotes are dropped.
+fn ini_value(contents: &str, section: &str, key: &str) -> Option<String> {
+    let mut current = "";
+    for line in contents.lines() {
+        let line = line.trim();
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            current = name;
+            continue;
+        }
+        if current != section {
+            continue;
+        }
+        let Some((k, v)) = line.split_once('=') else {
+            continue;
+        };
+        if k.trim() == key {
+            let value = v.trim().trim_matches(|c| c == '"' || c == '\'');
+            return (!value.is_empty()).then(|| value.to_string());
+        }
+    }
+    None
+}
+
+/// Groups identical values, e.g. `Adwaita [GTK2/3/4], Breeze [KDE]`.
+fn render(values: Vec<(&'static str, String)>) -> Result<Value, ModuleError> {
+    let mut groups: Vec<(String, Vec<&str>)> = Vec::new();
+    for (tag, value) in values {
+        match groups.iter_mut().find(|(v, _)| *v == value) {
+            Some((_, tags)) => tags.push(tag),
+            None => groups.push((value, vec![tag])),
+        }
+    }
+    if groups.is_empty() {
+        return Err(ModuleError::Unavailable);
+    }
+
+    let parts: Vec<String> = groups
+        .iter()
+        .map(|(value, tags)| format!("{} [{}]", value, join_tags(tags)))
+        .collect();
+    Ok(parts.join(", ").into())
+}
+
+/// `GTK2`, `GTK3` becomes `GTK2/3`.
+fn join_tags(tags: &[&str]) -> String {
+    if tags.len() > 1 && tags.iter().all(|tag| tag.starts_with("GTK")) {
+        let versions: Vec<&str> = tags.iter().map(|tag| &tag[3..]).collect();
+        return format!("GTK{}", versions.join("/"));
+    }
+    tags.join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::fixture::Fixture;
+    use std::path::Path;
+
+    fn gtk_theme(theme: &str) -> String {
+        let fixture = Fixture::new();
+        let ctx = fixture.context();
+        let ini = ctx.config_path("gtk-3.0/settings.ini").unwrap();
+        fs::create_dir_all(ini.parent().unwrap()).unwrap();
+        fs::write(&ini, format!("[Settings]\ngtk-theme-name={}\n", theme)).unwrap();
+        assert!(ini.starts_with(Path::new(&std::env::temp_dir())));
+        match Theme.collect(&ctx) {
+            Ok(Value::Text(text)) => text,
+            _ => panic!("theme not found"),
+        }
+    }
+
+    #[test]
+    fn settings_are_read_per_context() {
+        assert!(gtk_theme("Adwaita-dark").starts_with("Adwaita-dark [GTK3"));
+        assert!(gtk_theme("Breeze").starts_with("Breeze [GTK3"));
+    }
+}
@@ -0,0 +1,30 @@
+use super::{Context, Module, ModuleError, Value};
+use sysinfo::System;
+
+pub struct Uptime;
+
+impl Module for Uptime {
+    fn name(&self) -> &'static str {
+        "uptime"
+    }
+
+    fn label(&self) -> &'static str {
+        "Uptime"
+    }
+
+    fn description(&self) -> &'static str {
+        "Time since the system booted"
+    }
+
+    fn collect(&self, _ctx: &Context) -> Result<Value, ModuleError> {
+        let uptime = System::uptime();
+        let uptime_days = uptime / 86400;
+        let uptime_hours = (uptime % 86400) / 3600;
+        let uptime_minutes = (uptime % 3600) / 60;
+        Ok(format!(
+            "{} days, {} hours, {} mins",
+            uptime_days, uptime_hours, uptime_minutes
+        )
+        .into())
+    }
+}
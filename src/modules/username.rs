@@ -0,0 +1,21 @@
+use super::{Context, Module, ModuleError, Value};
+
+pub struct Username;
+
+impl Module for Username {
+    fn name(&self) -> &'static str {
+        "username"
+    }
+
+    fn label(&self) -> &'static str {
+        "User"
+    }
+
+    fn description(&self) -> &'static str {
+        "Name of the current user, shown in the title"
+    }
+
+    fn collect(&self, _ctx: &Context) -> Result<Value, ModuleError> {
+        Ok(whoami::username().into())
+    }
+}
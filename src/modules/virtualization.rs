@@ -0,0 +1,307 @@
+use super::{dmi, Context, Module, ModuleError, Value};
+use std::fs;
+
+/// DMI vendor/product substrings and the hypervisor they identify.
+const HYPERVISORS: &[(&str, &str)] = &[
+    ("KVM", "KVM"),
+    ("QEMU", "QEMU"),
+    ("VMware", "VMware"),
+    ("VirtualBox", "VirtualBox"),
+    ("innotek", "VirtualBox"),
+    ("Xen", "Xen"),
+    ("Amazon EC2", "Amazon EC2"),
+    ("Google Compute Engine", "Google Compute Engine"),
+    ("Parallels", "Parallels"),
+    ("Bochs", "Bochs"),
+    ("OpenStack", "OpenStack"),
+    ("BHYVE", "bhyve"),
+];
+
+/// `/proc/1/cgroup` path fragments and the runtime that creates them.
+const CGROUP_RUNTIMES: &[(&str, &str)] = &[
+    ("libpod", "Podman"),
+    ("docker", "Docker"),
+    ("kubepods", "Kubernetes"),
+    ("lxc", "LXC"),
+    ("machine.slice", "systemd-nspawn"),
+];
+
+pub struct Virtualization;
+
+impl Module for Virtualization {
+    fn name(&self) -> &'static str {
+        "virtualization"
+    }
+
+    fn label(&self) -> &'static str {
+        "Virtualization"
+    }
+
+    fn description(&self) -> &'static str {
+        "Container runtime and hypervisor nekofetch is running under"
+    }
+
+    fn collect(&self, ctx: &Context) -> Result<Value, ModuleError> {
+        let container = container(ctx);
+        let hypervisor = wsl(ctx).or_else(|| hypervisor(ctx));
+        let text = match (container, hypervisor) {
+            (Some(container), Some(hypervisor)) => {
+                format!("{} container on {}", container, hypervisor)
+            }
+            (Some(container), None) => format!("{} container", container),
+            (None, Some(hypervisor)) => hypervisor,
+            (None, None) => return Err(ModuleError::NotPresent),
+        };
+        Ok(text.into())
+    }
+}
+
+fn container(ctx: &Context) -> Option<String> {
+    let read = |path: &str| fs::read_to_string(ctx.path(path)).ok();
+
+    if let Some(env) = read("/run/.containerenv") {
+        // Podman writes `engine="podman-<version>"`; other engines reuse the file.
+        let engine = env
+            .lines()
+            .find_map(|line| line.strip_prefix("engine="))
+            .map(|engine| engine.trim_matches('"'));
+        return Some(match engine {
+            Some(engine) if !engine.starts_with("podman") => engine.to_string(),
+            _ => "Podman".to_string(),
+        });
+    }
+    if ctx.path("/.dockerenv").exists() {
+        return Some("Docker".to_string());
+    }
+
+    // systemd and most runtimes set `container=` for PID 1. Reading PID 1's
+    // environment needs root, so systemd's copy of it is tried first.
+    let container_var = read("/run/systemd/container")
+        .map(|value| value.trim().to_string())
+        .or_else(|| {
+            read("/proc/1/environ")?
+                .split('\0')
+                .find_map(|var| var.strip_prefix("container="))
+                .map(str::to_string)
+        });
+    if let Some(name) = container_var.filter(|name| !name.is_empty()) {
+        return Some(runtime_name(&name));
+    }
+
+    let cgroup = read("/proc/1/cgroup")?;
+    CGROUP_RUNTIMES
+        .iter()
+        .find(|(fragment, _)| cgroup.contains(fragment))
+        .map(|(_, runtime)| runtime.to_string())
+}
+
+/// Pretty names for the values of `container=`.
+fn runtime_name(name: &str) -> String {
+    match name {
+        "docker" => "Docker",
+        "podman" => "Podman",
+        "lxc" | "lxc-libvirt" => "LXC",
+        "systemd-nspawn" => "systemd-nspawn",
+        "oci" => "OCI",
+        "wsl" => "WSL",
+        other => other,
+    }
+    .to_string()
+}
+
+/// WSL kernels carry "microsoft" in their version string.
+fn wsl(ctx: &Context) -> Option<String> {
+    let version = fs::read_to_string(ctx.path("/proc/version")).ok()?;
+    let version = version.to_lowercase();
+    if !version.contains("microsoft") {
+        return None;
+    }
+    Some(
+        if version.contains("wsl2") || version.contains("microsoft-standard") {
+            "WSL2".to_string()
+        } else {
+            "WSL".to_string()
+        },
+    )
+}
+
+fn hypervisor(ctx: &Context) -> Option<String> {
+    let fields = ["sys_vendor", "product_name", "bios_vendor", "board_vendor"];
+    let dmi: Vec<String> = fields
+        .iter()
+        .filter_map(|field| dmi::read(ctx, field))
+        .collect();
+
+    if let Some((_, name)) = HYPERVISORS
+        .iter()
+        .find(|(needle, _)| dmi.iter().any(|value| value.contains(needle)))
+    {
+        return Some(name.to_string());
+    }
+    if dmi.iter().any(|value| value == "Microsoft Corporation")
+        && dmi.iter().any(|value| value == "Virtual Machine")
+    {
+        return Some("Hyper-V".to_string());
+    }
+
+    // Any other hypervisor still sets the CPUID hypervisor bit.
+    let cpuinfo = fs::read_to_string(ctx.path("/proc/cpuinfo")).ok()?;
+    cpuinfo
+        .lines()
+        .find(|line| line.starts_with("flags"))
+        .filter(|flags| flags.split_whitespace().any(|flag| flag == "hypervisor"))
+        .map(|_| "VM".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::fixture::Fixture;
+
+    fn detect(fixture: &Fixture) -> Option<String> {
+        match Virtualization.collect(&fixture.context()) {
+            Ok(Value::Text(text)) => Some(text),
+            Ok(Value::Lines(lines)) => panic!("expected one line, got {:?}", lines),
+            Err(ModuleError::NotPresent) => None,
+            Err(err) => panic!("unexpected error: {}", err),
+        }
+    }
+
+    #[test]
+    fn bare_metal_is_hidden() {
+        let fixture = Fixture::new();
+        fixture
+            .file("/proc/1/cgroup", "0::/init.scope\n")
+            .file("/proc/version", "Linux version 6.8.0 (gcc)\n")
+            .file("/sys/class/dmi/id/sys_vendor", "LENOVO\n")
+            .file("/proc/cpuinfo", "flags\t\t: fpu vme sse2\n");
+        assert_eq!(detect(&fixture), None);
+    }
+
+    #[test]
+    fn dockerenv() {
+        let fixture = Fixture::new();
+        fixture.file("/.dockerenv", "");
+        assert_eq!(detect(&fixture).as_deref(), Some("Docker container"));
+    }
+
+    #[test]
+    fn containerenv_podman() {
+        let fixture = Fixture::new();
+        fixture.file(
+            "/run/.containerenv",
+            "engine=\"podman-4.9.3\"\nname=\"web\"\n",
+        );
+        assert_eq!(detect(&fixture).as_deref(), Some("Podman container"));
+    }
+
+    #[test]
+    fn containerenv_without_engine() {
+        let fixture = Fixture::new();
+        fixture.file("/run/.containerenv", "");
+        assert_eq!(detect(&fixture).as_deref(), Some("Podman container"));
+    }
+
+    #[test]
+    fn containerenv_other_engine() {
+        let fixture = Fixture::new();
+        fixture.file("/run/.containerenv", "engine=\"buildah-1.33\"\n");
+        assert_eq!(detect(&fixture).as_deref(), Some("buildah-1.33 container"));
+    }
+
+    #[test]
+    fn systemd_container_file() {
+        let fixture = Fixture::new();
+        fixture.file("/run/systemd/container", "systemd-nspawn\n");
+        assert_eq!(
+            detect(&fixture).as_deref(),
+            Some("systemd-nspawn container")
+        );
+    }
+
+    #[test]
+    fn pid1_environ() {
+        let fixture = Fixture::new();
+        fixture.file(
+            "/proc/1/environ",
+            "PATH=/usr/bin\0container=lxc\0TERM=linux\0",
+        );
+        assert_eq!(detect(&fixture).as_deref(), Some("LXC container"));
+    }
+
+    #[test]
+    fn cgroup_fragments() {
+        for (cgroup, runtime) in [
+            ("0::/system.slice/docker-1234.scope\n", "Docker"),
+            ("0::/machine.slice/libpod-5678.scope\n", "Podman"),
+            ("11:memory:/kubepods/besteffort/pod1\n", "Kubernetes"),
+            ("0::/lxc.payload.web\n", "LXC"),
+            ("0::/machine.slice/machine-web.scope\n", "systemd-nspawn"),
+        ] {
+            let fixture = Fixture::new();
+            fixture.file("/proc/1/cgroup", cgroup);
+            let expected = format!("{} container", runtime);
+            assert_eq!(detect(&fixture), Some(expected), "cgroup {:?}", cgroup);
+        }
+    }
+
+    #[test]
+    fn wsl() {
+        let fixture = Fixture::new();
+        fixture.file(
+            "/proc/version",
+            "Linux version 5.15.153.1-microsoft-standard-WSL2 (root@1234)\n",
+        );
+        assert_eq!(detect(&fixture).as_deref(), Some("WSL2"));
+
+        let fixture = Fixture::new();
+        fixture.file("/proc/version", "Linux version 4.4.0-19041-Microsoft\n");
+        assert_eq!(detect(&fixture).as_deref(), Some("WSL"));
+    }
+
+    #[test]
+    fn hyper_v() {
+        let fixture = Fixture::new();
+        fixture
+            .file(
+                "/sys/devices/virtual/dmi/id/sys_vendor",
+                "Microsoft Corporation\n",
+            )
+            .file(
+                "/sys/devices/virtual/dmi/id/product_name",
+                "Virtual Machine\n",
+            );
+        assert_eq!(detect(&fixture).as_deref(), Some("Hyper-V"));
+    }
+
+    #[test]
+    fn microsoft_hardware_is_not_hyper_v() {
+        let fixture = Fixture::new();
+        fixture
+            .file(
+                "/sys/devices/virtual/dmi/id/sys_vendor",
+                "Microsoft Corporation\n",
+            )
+            .file(
+                "/sys/devices/virtual/dmi/id/product_name",
+                "Surface Laptop 5\n",
+            );
+        assert_eq!(detect(&fixture), None);
+    }
+
+    #[test]
+    fn container_on_hypervisor() {
+        let fixture = Fixture::new();
+        fixture
+            .file("/.dockerenv", "")
+            .file("/sys/class/dmi/id/sys_vendor", "QEMU\n")
+            .file(
+                "/sys/class/dmi/id/product_name",
+                "Standard PC (Q35 + ICH9, 2009)\n",
+            );
+        assert_eq!(
+            detect(&fixture).as_deref(),
+            Some("Docker container on QEMU")
+        );
+    }
+}
@@ -0,0 +1,138 @@
+use super::{Context, Module, ModuleError, Refresh, Value};
+use std::env;
+
+/// What a known process tells us about the graphical session.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Role {
+    /// An X11 window manager.
+    Wm,
+    /// A Wayland compositor, which is also the window manager.
+    WaylandCompositor,
+    /// A standalone X11 compositor running next to the WM.
+    Compositor,
+}
+
+/// Process names of known window managers and compositors. A trailing `*`
+/// matches any suffix, for binaries named after the build platform.
+const KNOWN: &[(&str, &str, Role)] = &[
+    ("sway", "Sway", Role::WaylandCompositor),
+    ("Hyprland", "Hyprland", Role::WaylandCompositor),
+    ("river", "river", Role::WaylandCompositor),
+    ("wayfire", "Wayfire", Role::WaylandCompositor),
+    ("labwc", "labwc", Role::WaylandCompositor),
+    ("niri", "niri", Role::WaylandCompositor),
+    ("dwl", "dwl", Role::WaylandCompositor),
+    ("hikari", "hikari", Role::WaylandCompositor),
+    ("weston", "Weston", Role::WaylandCompositor),
+    ("cage", "Cage", Role::WaylandCompositor),
+    ("cosmic-comp", "COSMIC", Role::WaylandCompositor),
+    ("gamescope", "gamescope", Role::WaylandCompositor),
+    ("kwin_wayland", "KWin", Role::WaylandCompositor),
+    ("kwin_x11", "KWin", Role::Wm),
+    ("gnome-shell", "Mutter", Role::Wm),
+    ("mutter", "Mutter", Role::Wm),
+    ("muffin", "Muffin", Role::Wm),
+    ("cinnamon", "Muffin", Role::Wm),
+    ("marco", "Marco", Role::Wm),
+    ("metacity", "Metacity", Role::Wm),
+    ("xfwm4", "Xfwm4", Role::Wm),
+    ("openbox", "Openbox", Role::Wm),
+    ("fluxbox", "Fluxbox", Role::Wm),
+    ("icewm", "IceWM", Role::Wm),
+    ("jwm", "JWM", Role::Wm),
+    ("fvwm", "FVWM", Role::Wm),
+    ("fvwm3", "FVWM3", Role::Wm),
+    ("wmaker", "Window Maker", Role::Wm),
+    ("enlightenment", "Enlightenment", Role::Wm),
+    ("i3", "i3", Role::Wm),
+    ("bspwm", "bspwm", Role::Wm),
+    ("awesome", "awesome", Role::Wm),
+    ("dwm", "dwm", Role::Wm),
+    ("herbstluftwm", "herbstluftwm", Role::Wm),
+    ("xmonad*", "xmonad", Role::Wm),
+    ("qtile", "Qtile", Role::Wm),
+    ("spectrwm", "spectrwm", Role::Wm),
+    ("leftwm", "LeftWM", Role::Wm),
+    ("ratpoison", "ratpoison", Role::Wm),
+    ("cwm", "cwm", Role::Wm),
+    ("picom", "picom", Role::Compositor),
+    ("compton", "compton", Role::Compositor),
+    ("xcompmgr", "xcompmgr", Role::Compositor),
+    ("compiz", "Compiz", Role::Compositor),
+];
+
+pub struct Wm;
+
+impl Module for Wm {
+    fn name(&self) -> &'static str {
+        "wm"
+    }
+
+    fn label(&self) -> &'static str {
+        "WM"
+    }
+
+    fn description(&self) -> &'static str {
+        "Window manager, compositor and session type"
+    }
+
+    fn refreshes(&self) -> &'static [Refresh] {
+        &[Refresh::Processes]
+    }
+
+    fn collect(&self, ctx: &Context) -> Result<Value, ModuleError> {
+        if cfg!(target_os = "macos") {
+            return Ok("Quartz Compositor".to_string().into());
+        }
+        if !cfg!(target_os = "linux") {
+            return Err(ModuleError::Unavailable);
+        }
+
+        let mut wm = None;
+        let mut compositor = None;
+        for process in ctx.sys.processes().values() {
+            let name = process.name().to_string_lossy();
+            let Some((_, display, role)) = KNOWN.iter().find(|(known, _, _)| matches(known, &name))
+            else {
+                continue;
+            };
+            match role {
+                Role::Compositor => compositor = compositor.or(Some(*display)),
+                // A Wayland compositor wins over an X11 WM running under Xwayland.
+                Role::WaylandCompositor => wm = Some((*display, *role)),
+                Role::Wm => wm = wm.or(Some((*display, *role))),
+            }
+        }
+
+        let (wm, role) = wm.ok_or(ModuleError::Unavailable)?;
+        let mut line = wm.to_string();
+        if let Some(compositor) = compositor {
+            line.push_str(&format!(" + {}", compositor));
+        }
+        let session = session_type().unwrap_or(match role {
+            Role::WaylandCompositor => "Wayland",
+            _ => "X11",
+        });
+        line.push_str(&format!(" ({})", session));
+        Ok(line.into())
+    }
+}
+
+fn matches(known: &str, name: &str) -> bool {
+    match known.strip_suffix('*') {
+        Some(prefix) => name.starts_with(prefix),
+        None => name == known,
+    }
+}
+
+/// X11 or Wayland, from the variables the session sets up.
+fn session_type() -> Option<&'static str> {
+    if env::var_os("WAYLAND_DISPLAY").is_some() {
+        return Some("Wayland");
+    }
+    match env::var("XDG_SESSION_TYPE").ok()?.as_str() {
+        "wayland" => Some("Wayland"),
+        "x11" => Some("X11"),
+        _ => None,
+    }
+}
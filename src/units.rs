@@ -0,0 +1,45 @@
+//! Byte size formatting shared by every module that prints sizes.
+
+use crate::config::{Scale, UnitSystem, UnitsConfig};
+
+const BINARY_UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+const DECIMAL_UNITS: [&str; 5] = ["B", "kB", "MB", "GB", "TB"];
+
+/// Formats `bytes` with the configured unit system, scale and precision.
+pub fn format_size(bytes: u64, units: &UnitsConfig) -> String {
+    let (base, names) = match units.system {
+        UnitSystem::Binary => (1024f64, BINARY_UNITS),
+        UnitSystem::Decimal => (1000f64, DECIMAL_UNITS),
+    };
+    let level = match units.scale {
+        Scale::Auto => {
+            let mut level = 0;
+            let mut value = bytes as f64;
+            while value >= base && level < names.len() - 1 {
+                value /= base;
+                level += 1;
+            }
+            level
+        }
+        Scale::Kilo => 1,
+        Scale::Mega => 2,
+        Scale::Giga => 3,
+        Scale::Tera => 4,
+    };
+    let value = bytes as f64 / base.powi(level as i32);
+    let precision = if level == 0 { 0 } else { units.precision };
+    format!("{:.*} {}", precision, value, names[level])
+}
+
+/// Formats `used / total`, with the percentage if the config asks for it.
+pub fn format_usage(used: u64, total: u64, units: &UnitsConfig) -> String {
+    let mut text = format!(
+        "{} / {}",
+        format_size(used, units),
+        format_size(total, units)
+    );
+    if units.show_percent && total > 0 {
+        text.push_str(&format!(" ({}%)", used * 100 / total));
+    }
+    text
+}